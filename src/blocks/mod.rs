@@ -0,0 +1,3 @@
+pub mod offset;
+pub mod properties;
+pub mod shape_registry;
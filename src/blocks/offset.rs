@@ -0,0 +1,95 @@
+/// original: BlockBehaviour.OffsetType
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OffsetType {
+    #[default]
+    None,
+    XZ,
+    XYZ,
+}
+
+/// Position-hash offset some plants apply to their shapes (bamboo, dripstone, flowers...).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BlockOffset {
+    pub offset_type: OffsetType,
+    /// original: getMaxHorizontalOffset, 0.25 unless overridden.
+    pub max_horizontal: f32,
+    /// original: getMaxVerticalOffset, 0.2 unless overridden.
+    pub max_vertical: f32,
+}
+
+impl Default for BlockOffset {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+/// original: Mth.getSeed
+fn get_seed(x: i32, y: i32, z: i32) -> i64 {
+    // x * 3129871 overflows as an int before widening, keep that.
+    let mut seed = (x.wrapping_mul(3129871) as i64) ^ (z as i64).wrapping_mul(116129781) ^ y as i64;
+    seed = seed
+        .wrapping_mul(seed)
+        .wrapping_mul(42317861)
+        .wrapping_add(seed.wrapping_mul(11));
+    seed >> 16
+}
+
+impl BlockOffset {
+    pub const NONE: Self = Self {
+        offset_type: OffsetType::None,
+        max_horizontal: 0.25,
+        max_vertical: 0.2,
+    };
+
+    pub fn xz(max_horizontal: f32) -> Self {
+        Self {
+            offset_type: OffsetType::XZ,
+            max_horizontal,
+            ..Self::NONE
+        }
+    }
+
+    pub fn xyz(max_horizontal: f32, max_vertical: f32) -> Self {
+        Self {
+            offset_type: OffsetType::XYZ,
+            max_horizontal,
+            max_vertical,
+        }
+    }
+
+    /// vanilla offset for a block at `block_pos` (block coordinates).
+    pub fn get_offset(&self, block_pos: &glam::Vec3A) -> glam::Vec3A {
+        if self.offset_type == OffsetType::None {
+            return glam::Vec3A::ZERO;
+        }
+
+        // y is always 0 here, shapes in the same column share an offset.
+        let seed = get_seed(block_pos.x as i32, 0, block_pos.z as i32);
+        let x = (((seed & 15) as f32 / 15.0 - 0.5) * 0.5)
+            .clamp(-self.max_horizontal, self.max_horizontal);
+        let y = if self.offset_type == OffsetType::XYZ {
+            (((seed >> 4) & 15) as f32 / 15.0 - 1.0) * self.max_vertical
+        } else {
+            0.0
+        };
+        let z = ((((seed >> 8) & 15) as f32 / 15.0 - 0.5) * 0.5)
+            .clamp(-self.max_horizontal, self.max_horizontal);
+
+        glam::Vec3A::new(x, y, z)
+    }
+
+    /// offsets of vanilla blocks that have one.
+    pub fn get_vanilla(name: &str) -> Self {
+        match name {
+            "pointed_dripstone" => Self::xz(0.125),
+            "bamboo" | "bamboo_sapling" | "hanging_roots" | "mangrove_propagule"
+            | "tall_grass" | "large_fern" | "dandelion" | "poppy" | "blue_orchid" | "allium"
+            | "azure_bluet" | "red_tulip" | "orange_tulip" | "white_tulip" | "pink_tulip"
+            | "oxeye_daisy" | "cornflower" | "lily_of_the_valley" | "wither_rose"
+            | "torchflower" | "sunflower" | "lilac" | "rose_bush" | "peony" | "warped_roots"
+            | "crimson_roots" | "nether_sprouts" => Self::xz(0.25),
+            "grass" | "short_grass" | "fern" | "small_dripleaf" => Self::xyz(0.25, 0.2),
+            _ => Self::NONE,
+        }
+    }
+}
@@ -0,0 +1,87 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Down,
+    Up,
+    North,
+    South,
+    West,
+    East,
+}
+
+impl Direction {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "down" => Some(Self::Down),
+            "up" => Some(Self::Up),
+            "north" => Some(Self::North),
+            "south" => Some(Self::South),
+            "west" => Some(Self::West),
+            "east" => Some(Self::East),
+            _ => None,
+        }
+    }
+}
+
+/// `half` for stairs/trapdoors (top/bottom) and doors/tall plants (upper/lower).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Half {
+    Top,
+    Bottom,
+    Upper,
+    Lower,
+}
+
+impl Half {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "top" => Some(Self::Top),
+            "bottom" => Some(Self::Bottom),
+            "upper" => Some(Self::Upper),
+            "lower" => Some(Self::Lower),
+            _ => None,
+        }
+    }
+}
+
+/// Block state properties physics cares about.
+///
+/// Anything a block doesn't have stays `None` (or `false` for waterlogged),
+/// so the simulators fall back to metadata like prismarine-physics does.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BlockProps {
+    pub waterlogged: bool,
+    /// fluid level, 0 is a source, 8+ is falling.
+    pub level: Option<u8>,
+    /// bubble columns: true pulls down (magma), false pushes up (soul sand).
+    pub drag: Option<bool>,
+    pub facing: Option<Direction>,
+    pub half: Option<Half>,
+    pub open: Option<bool>,
+    /// snow layers.
+    pub layers: Option<u8>,
+}
+
+impl BlockProps {
+    /// sets a property from its minecraft-data name/value pair.
+    /// unknown properties are ignored.
+    pub fn set(&mut self, name: &str, value: &str) {
+        match name {
+            "waterlogged" => self.waterlogged = value == "true",
+            "level" => self.level = value.parse().ok(),
+            "drag" => self.drag = Some(value == "true"),
+            "facing" => self.facing = Direction::from_name(value),
+            "half" => self.half = Half::from_name(value),
+            "open" => self.open = Some(value == "true"),
+            "layers" => self.layers = value.parse().ok(),
+            _ => {}
+        }
+    }
+
+    pub fn from_pairs<'a>(pairs: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let mut props = Self::default();
+        for (name, value) in pairs {
+            props.set(name, value);
+        }
+        props
+    }
+}
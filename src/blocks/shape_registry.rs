@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::{
+    blocks::{offset::BlockOffset, properties::BlockProps},
+    simulators::{Block, World},
+    world::border::WorldBorder,
+};
+
+/// what a block state resolves to.
+#[derive(Clone, Copy, Default)]
+struct StateEntry {
+    block_id: u32,
+    shape_id: u32,
+}
+
+/// one entry of minecraft-data's `blocks.json`, trimmed down to what physics cares about.
+#[derive(Clone, Default)]
+pub struct BlockInfo {
+    pub(crate) id: u32,
+    pub(crate) name: String,
+    pub(crate) min_state_id: u32,
+    pub(crate) max_state_id: u32,
+    /// original: "boundingBox", either "block" or "empty".
+    pub(crate) bounding_box: String,
+    pub(crate) states: Vec<StateProperty>,
+    pub(crate) offset: BlockOffset,
+}
+
+/// one of a block's state properties, in minecraft-data order.
+#[derive(Clone, Default)]
+pub struct StateProperty {
+    pub(crate) name: String,
+    /// every possible value, indexed the same way state ids are.
+    pub(crate) values: Vec<String>,
+}
+
+fn parse_state_property(state: &Value) -> Result<StateProperty, ShapeRegistryError> {
+    let name = state
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(|| malformed("state without \"name\""))?
+        .to_string();
+    let num_values = get_u32(state, "num_values")?;
+
+    let values = match state.get("type").and_then(Value::as_str) {
+        // minecraft-data omits the values of booleans, vanilla orders them true, false.
+        Some("bool") => vec!["true".to_string(), "false".to_string()],
+        _ => match state.get("values").and_then(Value::as_array) {
+            Some(values) => values
+                .iter()
+                .map(|v| v.as_str().map(str::to_string))
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| malformed(format!("values of {}", name)))?,
+            None => (0..num_values).map(|v| v.to_string()).collect(),
+        },
+    };
+    if values.len() != num_values as usize {
+        return Err(malformed(format!("values of {}", name)));
+    }
+
+    Ok(StateProperty { name, values })
+}
+
+impl BlockInfo {
+    pub fn get_id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[derive(Debug)]
+pub enum ShapeRegistryError {
+    Json(serde_json::Error),
+    /// field or block that was expected but missing/malformed.
+    Malformed(String),
+}
+
+impl fmt::Display for ShapeRegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(err) => write!(f, "invalid json: {}", err),
+            Self::Malformed(what) => write!(f, "malformed block data: {}", what),
+        }
+    }
+}
+
+impl std::error::Error for ShapeRegistryError {}
+
+impl From<serde_json::Error> for ShapeRegistryError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+/// Resolves block state ids to collision shapes.
+///
+/// Built from minecraft-data's `blocks.json` (for state id ranges)
+/// and `blockCollisionShapes.json` (for the shapes themselves).
+#[derive(Default)]
+pub struct ShapeRegistry {
+    blocks: Vec<BlockInfo>,
+    block_names: HashMap<String, u32>,
+    /// indexed by state id.
+    states: Vec<StateEntry>,
+    /// indexed by shape id.
+    shapes: Vec<Vec<[f32; 6]>>,
+}
+
+fn malformed(what: impl Into<String>) -> ShapeRegistryError {
+    ShapeRegistryError::Malformed(what.into())
+}
+
+fn get_u32(obj: &Value, key: &str) -> Result<u32, ShapeRegistryError> {
+    obj.get(key)
+        .and_then(Value::as_u64)
+        .map(|v| v as u32)
+        .ok_or_else(|| malformed(format!("missing \"{}\"", key)))
+}
+
+impl ShapeRegistry {
+    pub fn from_json(blocks_json: &str, shapes_json: &str) -> Result<Self, ShapeRegistryError> {
+        let blocks: Value = serde_json::from_str(blocks_json)?;
+        let shapes: Value = serde_json::from_str(shapes_json)?;
+        Self::from_values(&blocks, &shapes)
+    }
+
+    pub fn from_values(blocks: &Value, shapes: &Value) -> Result<Self, ShapeRegistryError> {
+        let mut registry = Self::default();
+
+        let shape_table = shapes
+            .get("shapes")
+            .and_then(Value::as_object)
+            .ok_or_else(|| malformed("missing \"shapes\""))?;
+        for (id, boxes) in shape_table {
+            let id: usize = id
+                .parse()
+                .map_err(|_| malformed(format!("bad shape id {}", id)))?;
+            let boxes = boxes
+                .as_array()
+                .ok_or_else(|| malformed(format!("shape {} is not a list", id)))?;
+
+            let mut parsed = Vec::with_capacity(boxes.len());
+            for bb in boxes {
+                let mut shape = [0.0; 6];
+                let coords = bb.as_array().filter(|c| c.len() == 6);
+                let coords = coords.ok_or_else(|| malformed(format!("shape {}", id)))?;
+                for (i, c) in coords.iter().enumerate() {
+                    shape[i] = c.as_f64().ok_or_else(|| malformed(format!("shape {}", id)))? as f32;
+                }
+                parsed.push(shape);
+            }
+
+            if registry.shapes.len() <= id {
+                registry.shapes.resize(id + 1, vec![]);
+            }
+            registry.shapes[id] = parsed;
+        }
+
+        let block_shapes = shapes
+            .get("blocks")
+            .and_then(Value::as_object)
+            .ok_or_else(|| malformed("missing \"blocks\""))?;
+
+        let block_list = blocks
+            .as_array()
+            .ok_or_else(|| malformed("blocks.json is not a list"))?;
+        for block in block_list {
+            let info = BlockInfo {
+                id: get_u32(block, "id")?,
+                name: block
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| malformed("missing \"name\""))?
+                    .to_string(),
+                min_state_id: get_u32(block, "minStateId")?,
+                max_state_id: get_u32(block, "maxStateId")?,
+                bounding_box: block
+                    .get("boundingBox")
+                    .and_then(Value::as_str)
+                    .unwrap_or("empty")
+                    .to_string(),
+                states: match block.get("states").and_then(Value::as_array) {
+                    Some(states) => states
+                        .iter()
+                        .map(parse_state_property)
+                        .collect::<Result<_, _>>()?,
+                    None => vec![],
+                },
+                offset: BlockOffset::default(),
+            };
+
+            // blocks missing from the shape file have no collision (air, flowers...).
+            let block_shape = block_shapes.get(&info.name);
+            if registry.states.len() <= info.max_state_id as usize {
+                registry
+                    .states
+                    .resize(info.max_state_id as usize + 1, StateEntry::default());
+            }
+            for state_id in info.min_state_id..=info.max_state_id {
+                let shape_id = match block_shape {
+                    None => 0,
+                    Some(Value::Array(per_state)) => per_state
+                        .get((state_id - info.min_state_id) as usize)
+                        .and_then(Value::as_u64)
+                        .ok_or_else(|| malformed(format!("shapes of {}", info.name)))?
+                        as u32,
+                    Some(single) => single
+                        .as_u64()
+                        .ok_or_else(|| malformed(format!("shapes of {}", info.name)))?
+                        as u32,
+                };
+                registry.states[state_id as usize] = StateEntry {
+                    block_id: info.id,
+                    shape_id,
+                };
+            }
+
+            if registry.blocks.len() <= info.id as usize {
+                registry.blocks.resize(info.id as usize + 1, BlockInfo::default());
+            }
+            registry.block_names.insert(info.name.clone(), info.id);
+            let id = info.id as usize;
+            registry.blocks[id] = BlockInfo {
+                // not part of minecraft-data, comes from the vanilla table.
+                offset: BlockOffset::get_vanilla(&info.name),
+                ..info
+            };
+        }
+
+        Ok(registry)
+    }
+
+    pub fn get_block_info(&self, block_id: u32) -> Option<&BlockInfo> {
+        self.blocks.get(block_id as usize).filter(|b| !b.name.is_empty())
+    }
+
+    pub fn get_block_id(&self, name: &str) -> Option<u32> {
+        self.block_names.get(name).copied()
+    }
+
+    /// overrides the offset of a block, for versions that differ from the vanilla table.
+    pub fn set_offset(&mut self, name: &str, offset: BlockOffset) -> bool {
+        match self.get_block_id(name) {
+            Some(id) => {
+                self.blocks[id as usize].offset = offset;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn get_block_info_by_state(&self, state_id: u32) -> Option<&BlockInfo> {
+        let entry = self.states.get(state_id as usize)?;
+        self.get_block_info(entry.block_id)
+    }
+
+    /// collision shapes of a state, relative to the block's origin.
+    pub fn get_shapes(&self, state_id: u32) -> &[[f32; 6]] {
+        self.states
+            .get(state_id as usize)
+            .and_then(|entry| self.shapes.get(entry.shape_id as usize))
+            .map(|s| s.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// decodes a state id into its properties.
+    /// the last property varies fastest, same as vanilla's state ordering.
+    pub fn get_properties(&self, state_id: u32) -> BlockProps {
+        let mut props = BlockProps::default();
+        let info = match self.get_block_info_by_state(state_id) {
+            Some(info) => info,
+            None => return props,
+        };
+
+        let mut data = state_id - info.min_state_id;
+        for state in info.states.iter().rev() {
+            let num_values = state.values.len() as u32;
+            props.set(&state.name, &state.values[(data % num_values) as usize]);
+            data /= num_values;
+        }
+        props
+    }
+
+    /// builds the `Block` the simulators expect out of a state id.
+    pub fn get_block(&self, state_id: u32, position: glam::Vec3A) -> Option<Block> {
+        let info = self.get_block_info_by_state(state_id)?;
+        let shapes = self.get_shapes(state_id).to_vec();
+        Some(Block {
+            bounding_box: if shapes.is_empty() {
+                "empty".to_string()
+            } else {
+                info.bounding_box.clone()
+            },
+            // matches prismarine-block for post-flattening versions.
+            metadata: state_id - info.min_state_id,
+            b_type: info.id,
+            position,
+            shapes,
+            properties: self.get_properties(state_id),
+            offset: info.offset,
+        })
+    }
+}
+
+/// A world that only knows block state ids.
+pub trait StateWorld {
+    fn get_block_state(&self, pos: &glam::Vec3A) -> Option<u32>;
+
+    fn get_world_border(&self) -> Option<WorldBorder> {
+        None
+    }
+
+    fn get_min_y(&self) -> Option<f32> {
+        None
+    }
+}
+
+/// Turns a `StateWorld` into a `World` by resolving states through a `ShapeRegistry`.
+pub struct RegistryWorld<'a, W: StateWorld> {
+    registry: &'a ShapeRegistry,
+    world: W,
+}
+
+impl<'a, W: StateWorld> RegistryWorld<'a, W> {
+    pub fn new(registry: &'a ShapeRegistry, world: W) -> Self {
+        Self { registry, world }
+    }
+
+    pub fn get_registry(&self) -> &ShapeRegistry {
+        self.registry
+    }
+
+    pub fn get_inner(&self) -> &W {
+        &self.world
+    }
+}
+
+impl<'a, W: StateWorld> World for RegistryWorld<'a, W> {
+    fn get_block(&self, pos: &glam::Vec3A) -> Option<Block> {
+        let state_id = self.world.get_block_state(pos)?;
+        self.registry.get_block(state_id, pos.floor())
+    }
+
+    fn get_world_border(&self) -> Option<WorldBorder> {
+        self.world.get_world_border()
+    }
+
+    fn get_min_y(&self) -> Option<f32> {
+        self.world.get_min_y()
+    }
+}
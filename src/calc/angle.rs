@@ -0,0 +1,95 @@
+use std::f32::consts::PI;
+
+/// Rotation the way `EntityState` (and mineflayer) keeps it: radians,
+/// yaw 0 faces -z and turning left (towards -x) increases it, pitch up is positive.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rotation {
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+/// Rotation the way the protocol (and vanilla) sends it: degrees,
+/// yaw 0 faces +z and turning right (towards -x) increases it, pitch down is positive.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NotchianRotation {
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+impl Rotation {
+    pub fn new(yaw: f32, pitch: f32) -> Self {
+        Self { yaw, pitch }
+    }
+
+    /// original (mineflayer): fromNotchianYaw/fromNotchianPitch
+    pub fn from_notchian(rotation: NotchianRotation) -> Self {
+        Self {
+            yaw: PI - rotation.yaw.to_radians(),
+            pitch: -rotation.pitch.to_radians(),
+        }
+    }
+
+    /// original (mineflayer): toNotchianYaw/toNotchianPitch
+    pub fn to_notchian(self) -> NotchianRotation {
+        NotchianRotation {
+            yaw: (PI - self.yaw).to_degrees(),
+            pitch: -self.pitch.to_degrees(),
+        }
+    }
+
+    /// facing along `direction`, which doesn't need to be normalized.
+    pub fn from_direction(direction: glam::Vec3A) -> Self {
+        Self {
+            yaw: (-direction.x).atan2(-direction.z),
+            pitch: direction
+                .y
+                .atan2(glam::Vec2::new(direction.x, direction.z).length()),
+        }
+    }
+
+    /// unit look vector.
+    pub fn get_direction(self) -> glam::Vec3A {
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        glam::Vec3A::new(-sin_yaw * cos_pitch, sin_pitch, -cos_yaw * cos_pitch)
+    }
+
+    /// the yaw vanilla's movement trig expects, in radians.
+    /// original: Entity.getYRot() * (PI / 180)
+    pub fn get_vanilla_yaw(self) -> f32 {
+        PI - self.yaw
+    }
+}
+
+impl NotchianRotation {
+    pub fn new(yaw: f32, pitch: f32) -> Self {
+        Self { yaw, pitch }
+    }
+
+    /// yaw within [-180, 180).
+    /// original: Mth.wrapDegrees
+    pub fn wrapped(self) -> Self {
+        Self {
+            yaw: (self.yaw + 180.0).rem_euclid(360.0) - 180.0,
+            pitch: self.pitch,
+        }
+    }
+
+    pub fn get_direction(self) -> glam::Vec3A {
+        Rotation::from(self).get_direction()
+    }
+}
+
+impl From<NotchianRotation> for Rotation {
+    fn from(rotation: NotchianRotation) -> Self {
+        Self::from_notchian(rotation)
+    }
+}
+
+impl From<Rotation> for NotchianRotation {
+    fn from(rotation: Rotation) -> Self {
+        rotation.to_notchian()
+    }
+}
@@ -1 +1,2 @@
 pub mod aabb;
+pub mod angle;
@@ -1,10 +1,12 @@
 #![feature(is_some_and)]
 #![feature(core_intrinsics)]
 
+pub mod blocks;
 pub mod calc;
 pub mod settings;
 pub mod simulators;
 pub mod states;
+pub mod world;
 
 pub fn add(left: usize, right: usize) -> usize {
     left + right
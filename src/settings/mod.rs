@@ -2,6 +2,7 @@ pub mod physics_settings;
 
 
 #[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PlayerAttributeModifier {
     pub(crate) uuid: String,
     pub(crate) operation: u16,
@@ -9,6 +10,7 @@ pub struct PlayerAttributeModifier {
 }
 
 #[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PlayerAttribute {
     value: f32,
     modifiers: Vec<PlayerAttributeModifier>,
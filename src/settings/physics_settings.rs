@@ -31,6 +31,9 @@ pub const DEFAULT_SLIPPERINESS: f32 = 0.6;
 
 pub const OUT_OF_LIQUID_IMPULSE: f32 = 0.3;
 
+/// how far below the minimum build height entities fall into the void.
+pub const BELOW_WORLD_DEPTH: f32 = 64.0;
+
 pub const AUTO_JUMP_COOLDOWN: u8 = 10; // ticks (0.5s)
 
 pub const BUBBLE_COLUMN_SURFACE_DRAG: BubbleColumnInfo = BubbleColumnInfo {
@@ -0,0 +1,71 @@
+use rayon::prelude::*;
+
+use crate::states::physics_context::EntityPhysicsContext;
+
+use super::{Simulator, TickSummary, World};
+
+/// Steps many entities at once, spread over a thread pool owned by the batch.
+///
+/// Entities don't see each other, so every entity ends up exactly where
+/// sequential `Simulator::tick` calls would have put it.
+pub struct BatchSimulator<S: Simulator + Sync> {
+    simulator: S,
+    pool: rayon::ThreadPool,
+}
+
+impl<S: Simulator + Sync> BatchSimulator<S> {
+    /// `threads` of 0 lets rayon pick (one per core).
+    pub fn new(simulator: S, threads: usize) -> Result<Self, rayon::ThreadPoolBuildError> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|i| format!("physics-batch-{}", i))
+            .build()?;
+        Ok(Self { simulator, pool })
+    }
+
+    pub fn get_simulator(&self) -> &S {
+        &self.simulator
+    }
+
+    pub fn get_thread_count(&self) -> usize {
+        self.pool.current_num_threads()
+    }
+
+    pub fn tick_all<W: World + Sync>(&self, ctxs: &mut [EntityPhysicsContext], world: &W) {
+        let simulator = &self.simulator;
+        self.pool.install(|| {
+            ctxs.par_iter_mut()
+                .for_each(|ctx| simulator.tick(ctx, world));
+        });
+    }
+
+    /// one tick for every entity, summaries in the same order as `ctxs`.
+    pub fn step_all<W: World + Sync>(
+        &self,
+        ctxs: &mut [EntityPhysicsContext],
+        world: &W,
+    ) -> Vec<TickSummary> {
+        let simulator = &self.simulator;
+        self.pool.install(|| {
+            ctxs.par_iter_mut()
+                .map(|ctx| simulator.step(ctx, world))
+                .collect()
+        })
+    }
+
+    pub fn simulate_ticks_all<W: World + Sync>(
+        &self,
+        ctxs: &mut [EntityPhysicsContext],
+        ticks: u32,
+        world: &W,
+    ) {
+        let simulator = &self.simulator;
+        self.pool.install(|| {
+            ctxs.par_iter_mut().for_each(|ctx| {
+                for _ in 0..ticks {
+                    simulator.tick(ctx, world);
+                }
+            });
+        });
+    }
+}
@@ -0,0 +1,94 @@
+use crate::calc::aabb::AABB;
+
+use super::World;
+
+/// every block shape touching `query_bb` (and the layer below it, for fences).
+pub fn get_surrounding_block_bbs(query_bb: &AABB, world: &impl World) -> Vec<AABB> {
+    let mut surrounding_bbs = vec![];
+    let q_bb_fl = query_bb.floored();
+    let mut cursor = glam::Vec3A::new(q_bb_fl.min_x, q_bb_fl.min_y - 1.0, q_bb_fl.min_z);
+    while cursor.y <= q_bb_fl.max_y {
+        cursor.z = q_bb_fl.min_z;
+        while cursor.z <= q_bb_fl.max_z {
+            cursor.x = q_bb_fl.min_x;
+            while cursor.x <= q_bb_fl.max_x {
+                if let Some(block) = world.get_block(&cursor) {
+                    let b_pos = block.get_shape_origin();
+                    for shape in block.shapes {
+                        let bb =
+                            AABB::new(shape[0], shape[1], shape[2], shape[3], shape[4], shape[5])
+                                .offset(b_pos.x, b_pos.y, b_pos.z);
+                        surrounding_bbs.push(bb);
+                    }
+                }
+                cursor.x += 1.0;
+            }
+            cursor.z += 1.0;
+        }
+        cursor.y += 1.0;
+    }
+    surrounding_bbs
+}
+
+/// block shapes plus the world border, what entities actually collide with when moving.
+pub fn get_collision_bbs(entity_bb: &AABB, query_bb: &AABB, world: &impl World) -> Vec<AABB> {
+    let mut collision_bbs = get_surrounding_block_bbs(query_bb, world);
+    if let Some(border) = world.get_world_border() {
+        collision_bbs.extend(border.get_collision_bbs(entity_bb, query_bb));
+    }
+    collision_bbs
+}
+
+/// original: Level.noCollision
+pub fn no_collision(bb: &AABB, world: &impl World) -> bool {
+    !get_collision_bbs(bb, bb, world)
+        .iter()
+        .any(|block_bb| block_bb.intersects(bb))
+}
+
+/// original: Entity.collideWithShapes
+/// y first, then whichever horizontal axis moves the most, then the other one.
+pub fn collide_with_shapes(vel: glam::Vec3A, bb: &AABB, shapes: &[AABB]) -> glam::Vec3A {
+    let (mut dx, mut dy, mut dz) = (vel.x, vel.y, vel.z);
+    let mut bb = *bb;
+
+    if dy != 0.0 {
+        for shape in shapes {
+            dy = shape.compute_offset_y(&bb, dy);
+        }
+        bb = bb.offset(0.0, dy, 0.0);
+    }
+
+    let z_first = dx.abs() < dz.abs();
+    if z_first && dz != 0.0 {
+        for shape in shapes {
+            dz = shape.compute_offset_z(&bb, dz);
+        }
+        bb = bb.offset(0.0, 0.0, dz);
+    }
+
+    if dx != 0.0 {
+        for shape in shapes {
+            dx = shape.compute_offset_x(&bb, dx);
+        }
+        bb = bb.offset(dx, 0.0, 0.0);
+    }
+
+    if !z_first && dz != 0.0 {
+        for shape in shapes {
+            dz = shape.compute_offset_z(&bb, dz);
+        }
+    }
+
+    glam::Vec3A::new(dx, dy, dz)
+}
+
+/// original: Entity.collideBoundingBox
+pub fn collide_bounding_box(vel: glam::Vec3A, bb: &AABB, world: &impl World) -> glam::Vec3A {
+    if vel == glam::Vec3A::ZERO {
+        return vel;
+    }
+    let query_bb = bb.extend(vel.x, vel.y, vel.z);
+    let shapes = get_collision_bbs(bb, &query_bb, world);
+    collide_with_shapes(vel, bb, &shapes)
+}
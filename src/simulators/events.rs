@@ -0,0 +1,84 @@
+use crate::states::physics_context::EntityPhysicsContext;
+
+/// Something that happened during a tick. Collected in the order it happened.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PhysicsEvent {
+    /// touched the ground after falling `fall_distance` blocks.
+    Landed {
+        fall_distance: f32,
+    },
+    /// started colliding horizontally.
+    HitWall,
+    /// entered water.
+    StartedSwimming,
+    /// left water.
+    StoppedSwimming,
+    EnteredLava,
+    EnteredWeb,
+    /// bounced off a slime block, `velocity` is the new upwards velocity.
+    SlimeBounce {
+        velocity: f32,
+    },
+    /// pushed up a ladder/vine this tick, once per tick while climbing.
+    ClimbedLadder,
+    /// stepped onto something up to `height` higher.
+    SteppedUp {
+        height: f32,
+    },
+}
+
+/// Receives events through `Simulator::tick_with_listener`.
+pub trait EventListener {
+    fn on_event(&mut self, ctx: &EntityPhysicsContext, event: &PhysicsEvent);
+}
+
+impl<F: FnMut(&EntityPhysicsContext, &PhysicsEvent)> EventListener for F {
+    fn on_event(&mut self, ctx: &EntityPhysicsContext, event: &PhysicsEvent) {
+        self(ctx, event)
+    }
+}
+
+/// original: Entity.checkFallDamage, plus the landing/wall events.
+/// call once the flags of a move are set.
+pub(crate) fn track_move(
+    entity: &mut EntityPhysicsContext,
+    was_on_ground: bool,
+    was_collided_horizontally: bool,
+    moved_y: f32,
+) {
+    if entity.state.is_collided_horizontally && !was_collided_horizontally {
+        entity.emit(PhysicsEvent::HitWall);
+    }
+    if entity.state.on_ground {
+        if !was_on_ground {
+            let fall_distance = entity.state.fall_distance;
+            entity.emit(PhysicsEvent::Landed { fall_distance });
+        }
+        entity.state.fall_distance = 0.0;
+    } else if moved_y < 0.0 {
+        entity.state.fall_distance -= moved_y;
+    }
+}
+
+/// fluid transitions. water cancels falling, lava halves it every tick.
+pub(crate) fn track_fluids(
+    entity: &mut EntityPhysicsContext,
+    was_in_water: bool,
+    was_in_lava: bool,
+) {
+    if entity.state.is_in_water {
+        entity.state.fall_distance = 0.0;
+        if !was_in_water {
+            entity.emit(PhysicsEvent::StartedSwimming);
+        }
+    } else if was_in_water {
+        entity.emit(PhysicsEvent::StoppedSwimming);
+    }
+
+    if entity.state.is_in_lava {
+        entity.state.fall_distance *= 0.5;
+        if !was_in_lava {
+            entity.emit(PhysicsEvent::EnteredLava);
+        }
+    }
+}
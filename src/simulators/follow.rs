@@ -0,0 +1,292 @@
+use crate::{
+    calc::angle::Rotation,
+    settings::physics_settings,
+    states::{
+        physics_context::EntityPhysicsContext,
+        player_context::{ControlStateHandler, TickInput},
+        EntityState,
+    },
+};
+
+use super::{Simulator, World};
+
+/// how far (horizontally) from the last block's center counts as stopped on it.
+pub const DEFAULT_ARRIVE_DISTANCE: f32 = 0.2;
+/// how many ticks every option is predicted for.
+pub const DEFAULT_LOOKAHEAD: u32 = 20;
+
+/// how one option plays out when held.
+#[derive(Clone, Copy, PartialEq)]
+enum Outcome {
+    /// in the target block after this many ticks.
+    Reached(u32),
+    /// neither there nor lost yet.
+    Pending,
+    /// landed off the path or fell below it.
+    Failed,
+}
+
+/// Turns a list of waypoints into inputs, one tick at a time.
+///
+/// Every tick the few sensible key combinations are predicted with the simulator:
+/// the quickest one that gets to the next waypoint wins. Jumps are only pressed when
+/// the waypoint is higher, or when walking on would leave the ground right now.
+/// On the last waypoint it picks whatever comes to rest closest to the block's center.
+pub struct PathFollower<S: Simulator> {
+    simulator: S,
+    waypoints: Vec<glam::IVec3>,
+    /// the waypoint being headed for.
+    index: usize,
+    finished: bool,
+    arrive_distance: f32,
+    lookahead: u32,
+}
+
+impl<S: Simulator> PathFollower<S> {
+    /// `waypoints` are feet blocks, the first one being where the entity starts.
+    pub fn new(simulator: S, waypoints: Vec<glam::IVec3>) -> Self {
+        Self {
+            simulator,
+            index: if waypoints.len() > 1 { 1 } else { 0 },
+            finished: waypoints.is_empty(),
+            waypoints,
+            arrive_distance: DEFAULT_ARRIVE_DISTANCE,
+            lookahead: DEFAULT_LOOKAHEAD,
+        }
+    }
+
+    pub fn with_arrive_distance(mut self, arrive_distance: f32) -> Self {
+        self.arrive_distance = arrive_distance;
+        self
+    }
+
+    pub fn with_lookahead(mut self, lookahead: u32) -> Self {
+        self.lookahead = lookahead;
+        self
+    }
+
+    pub fn get_simulator(&self) -> &S {
+        &self.simulator
+    }
+
+    pub fn get_waypoints(&self) -> &[glam::IVec3] {
+        &self.waypoints
+    }
+
+    /// the waypoint being headed for, none once finished.
+    pub fn get_target(&self) -> Option<glam::IVec3> {
+        if self.finished {
+            None
+        } else {
+            self.waypoints.get(self.index).copied()
+        }
+    }
+
+    /// stopped on the last waypoint.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// what to press this tick. predicts on a copy of `ctx`, apply the input and tick it.
+    pub fn next_input(&mut self, ctx: &EntityPhysicsContext, world: &impl World) -> TickInput {
+        let start = ctx.state.clone();
+        let idle = TickInput::new(ControlStateHandler::default(), start.rotation);
+        if self.finished {
+            return idle;
+        }
+
+        let feet = start.position.floor().as_ivec3();
+        let last = self.waypoints.len() - 1;
+        // whatever waypoint we're in is done with, overshoots included.
+        if let Some(i) = self.waypoints[self.index..]
+            .iter()
+            .rposition(|&wp| wp == feet)
+        {
+            self.index = (self.index + i + 1).min(last);
+        }
+
+        let target = self.waypoints[self.index];
+        let center = target.as_vec3a() + glam::Vec3A::new(0.5, 0.0, 0.5);
+        let offset = center - start.position;
+        let horizontal = glam::Vec2::new(offset.x, offset.z);
+        let rotation = if horizontal.length() > 1.0e-3 {
+            Rotation::from_direction(glam::Vec3A::new(offset.x, 0.0, offset.z))
+        } else {
+            Rotation::new(start.rotation.yaw, 0.0)
+        };
+
+        let mut ctx = ctx.clone();
+        if self.index == last && feet == target && start.on_ground {
+            let speed = glam::Vec2::new(start.velocity.x, start.velocity.z).length();
+            if horizontal.length() <= self.arrive_distance
+                && speed < physics_settings::NEGLIGEABLE_VELOCITY
+            {
+                self.finished = true;
+                return idle;
+            }
+            self.stop(&mut ctx, &start, center, rotation, world)
+        } else {
+            self.steer(&mut ctx, &start, target, rotation, world)
+        }
+    }
+
+    /// heads for `target`, see the struct docs.
+    fn steer(
+        &self,
+        ctx: &mut EntityPhysicsContext,
+        start: &EntityState,
+        target: glam::IVec3,
+        rotation: Rotation,
+        world: &impl World,
+    ) -> TickInput {
+        let sprint = self.is_straight();
+        let controls = |forward: bool, jump: bool, sprint: bool| ControlStateHandler {
+            forward,
+            jump,
+            sprint,
+            ..Default::default()
+        };
+        let walking = [controls(true, false, true), controls(true, false, false)];
+        let jumping = [controls(true, true, true), controls(true, true, false)];
+
+        let mut best: Option<(Outcome, Option<u32>, ControlStateHandler)> = None;
+        for c in walking.into_iter().filter(|c| sprint || !c.sprint) {
+            let (outcome, left_ground) = self.rollout(ctx, start, c, rotation, target, world);
+            if Self::is_better(outcome, best.map(|b| b.0)) {
+                best = Some((outcome, left_ground, c));
+            }
+        }
+
+        let (outcome, left_ground, _) = best.expect("there's always a walking option");
+        // walking would leave the ground right now without getting there: an edge.
+        let at_edge = outcome == Outcome::Failed && left_ground == Some(1);
+        let climbing =
+            target.y > start.position.floor().y as i32 || start.is_in_water || !start.on_ground;
+        if at_edge || climbing {
+            for c in jumping.into_iter().filter(|c| sprint || !c.sprint) {
+                let (outcome, left_ground) = self.rollout(ctx, start, c, rotation, target, world);
+                let better = match outcome {
+                    Outcome::Reached(_) => Self::is_better(outcome, best.map(|b| b.0)),
+                    // a doomed jump still beats walking off.
+                    _ => at_edge && best.map(|b| (b.0, b.2.jump)) == Some((Outcome::Failed, false)),
+                };
+                if better {
+                    best = Some((outcome, left_ground, c));
+                }
+            }
+        }
+
+        TickInput::new(best.expect("there's always a walking option").2, rotation)
+    }
+
+    /// reached beats pending beats failed, sooner beats later.
+    fn is_better(outcome: Outcome, best: Option<Outcome>) -> bool {
+        let rank = |o: Outcome| match o {
+            Outcome::Reached(ticks) => (0, ticks),
+            Outcome::Pending => (1, 0),
+            Outcome::Failed => (2, 0),
+        };
+        match best {
+            Some(best) => rank(outcome) < rank(best),
+            None => true,
+        }
+    }
+
+    /// holds `controls` for `lookahead` ticks.
+    /// returns how it went and which tick it first left the ground on.
+    fn rollout(
+        &self,
+        ctx: &mut EntityPhysicsContext,
+        start: &EntityState,
+        controls: ControlStateHandler,
+        rotation: Rotation,
+        target: glam::IVec3,
+        world: &impl World,
+    ) -> (Outcome, Option<u32>) {
+        ctx.state = start.clone();
+        let path = &self.waypoints[self.index.saturating_sub(1)..];
+        let lowest = path.iter().map(|wp| wp.y).min().unwrap_or(target.y);
+
+        let mut left_ground = None;
+        for tick in 1..=self.lookahead {
+            TickInput::new(controls, rotation).apply(ctx);
+            self.simulator.tick(ctx, world);
+
+            let state = &ctx.state;
+            let feet = state.position.floor().as_ivec3();
+            if feet == target {
+                return (Outcome::Reached(tick), left_ground);
+            }
+            if !state.on_ground {
+                left_ground.get_or_insert(tick);
+            } else if left_ground.is_some() && !path.contains(&feet) {
+                return (Outcome::Failed, left_ground);
+            }
+            if feet.y < lowest {
+                return (Outcome::Failed, left_ground);
+            }
+        }
+        (Outcome::Pending, left_ground)
+    }
+
+    /// on the last block: whatever comes to rest closest to its center.
+    fn stop(
+        &self,
+        ctx: &mut EntityPhysicsContext,
+        start: &EntityState,
+        center: glam::Vec3A,
+        rotation: Rotation,
+        world: &impl World,
+    ) -> TickInput {
+        let options = [
+            (true, false, true),
+            (true, false, false),
+            (false, false, false),
+            (false, true, false),
+        ];
+        let mut best: Option<(f32, TickInput)> = None;
+        for (forward, back, sprint) in options {
+            let input = TickInput::new(
+                ControlStateHandler {
+                    forward,
+                    back,
+                    sprint,
+                    ..Default::default()
+                },
+                rotation,
+            );
+            ctx.state = start.clone();
+            input.apply(ctx);
+            self.simulator.tick(ctx, world);
+            // then let go and coast.
+            ctx.state.control_states = ControlStateHandler::default();
+            self.simulator.simulate_ticks(ctx, self.lookahead, world);
+
+            let rest = ctx.state.position - center;
+            let error = glam::Vec2::new(rest.x, rest.z).length();
+            // the quickest option that ends up close enough, otherwise the closest.
+            let better = match best {
+                Some((best_error, _)) => best_error > self.arrive_distance && error < best_error,
+                None => true,
+            };
+            if better {
+                best = Some((error, input));
+            }
+        }
+        best.expect("there's always an option").1
+    }
+
+    /// the path keeps going the same way after the current waypoint, or it's the last one.
+    fn is_straight(&self) -> bool {
+        let direction = |from: glam::IVec3, to: glam::IVec3| {
+            let d = to - from;
+            glam::Vec2::new(d.x as f32, d.z as f32).normalize_or_zero()
+        };
+        let previous = self.waypoints[self.index.saturating_sub(1)];
+        let target = self.waypoints[self.index];
+        match self.waypoints.get(self.index + 1) {
+            Some(&next) => direction(previous, target).dot(direction(target, next)) > 0.9,
+            None => true,
+        }
+    }
+}
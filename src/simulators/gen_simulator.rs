@@ -1,6 +1,1082 @@
+use std::collections::{HashMap, HashSet};
 
+use crate::{
+    blocks::shape_registry::ShapeRegistry,
+    calc::aabb::AABB,
+    settings::{physics_settings, PlayerAttribute, PlayerAttributeModifier},
+    states::physics_context::EntityPhysicsContext,
+};
 
-/// todo, my friends.
+use super::{
+    collision,
+    events::{self, PhysicsEvent},
+    trace::{BlockEffect, TraceStage},
+    Block, Simulator, World,
+};
+
+/// never matches a real block id.
+const NO_BLOCK: u32 = u32::MAX;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Fluid {
+    Water,
+    Lava,
+}
+
+/// Generic entity simulator, modeled on modern (1.20) vanilla:
+/// `LivingEntity.aiStep`/`travel` and `Entity.move`.
+///
+/// Everything only players do (input scaling, sprinting, backing off edges while sneaking...)
+/// lives in the `player_*` functions and is skipped for other entities.
 pub struct GenSimulator {
+    air_ids: HashSet<u32>,
+    slime_block_id: u32,
+    web_id: u32,
+    bubblecolumn_id: u32,
+    water_id: u32,
+    lava_id: u32,
+    water_like: HashSet<u32>,
+    climbable: HashSet<u32>,
+    movement_speed_attribute: String,
+    block_slipperiness: HashMap<u32, f32>,
+    block_speed_factor: HashMap<u32, f32>,
+    block_jump_factor: HashMap<u32, f32>,
+}
+
+impl Default for GenSimulator {
+    /// knows no blocks at all, everything is a plain solid/empty block.
+    fn default() -> Self {
+        Self {
+            air_ids: HashSet::new(),
+            slime_block_id: NO_BLOCK,
+            web_id: NO_BLOCK,
+            bubblecolumn_id: NO_BLOCK,
+            water_id: NO_BLOCK,
+            lava_id: NO_BLOCK,
+            water_like: HashSet::new(),
+            climbable: HashSet::new(),
+            movement_speed_attribute: "generic.movement_speed".to_string(),
+            block_slipperiness: HashMap::new(),
+            block_speed_factor: HashMap::new(),
+            block_jump_factor: HashMap::new(),
+        }
+    }
+}
+
+/// original: FluidState.getAmount, from the block's `level`.
+fn level_to_amount(level: u32) -> u8 {
+    if level == 0 || level >= 8 {
+        8
+    } else {
+        8 - level as u8
+    }
+}
+
+impl GenSimulator {
+    pub fn from_registry(registry: &ShapeRegistry) -> Self {
+        let id = |name: &str| registry.get_block_id(name).unwrap_or(NO_BLOCK);
+        let ids = |names: &[&str]| -> HashSet<u32> {
+            names
+                .iter()
+                .filter_map(|name| registry.get_block_id(name))
+                .collect()
+        };
+        let table = |entries: &[(&str, f32)]| -> HashMap<u32, f32> {
+            entries
+                .iter()
+                .filter_map(|(name, v)| registry.get_block_id(name).map(|id| (id, *v)))
+                .collect()
+        };
+
+        Self {
+            air_ids: ids(&["air", "cave_air", "void_air"]),
+            slime_block_id: id("slime_block"),
+            web_id: id("cobweb"),
+            bubblecolumn_id: id("bubble_column"),
+            water_id: id("water"),
+            lava_id: id("lava"),
+            water_like: ids(&[
+                "seagrass",
+                "tall_seagrass",
+                "kelp",
+                "kelp_plant",
+                "bubble_column",
+            ]),
+            climbable: ids(&[
+                "ladder",
+                "vine",
+                "scaffolding",
+                "weeping_vines",
+                "weeping_vines_plant",
+                "twisting_vines",
+                "twisting_vines_plant",
+                "cave_vines",
+                "cave_vines_plant",
+            ]),
+            movement_speed_attribute: "generic.movement_speed".to_string(),
+            block_slipperiness: table(&[
+                ("slime_block", 0.8),
+                ("ice", 0.98),
+                ("packed_ice", 0.98),
+                ("frosted_ice", 0.98),
+                ("blue_ice", 0.989),
+            ]),
+            block_speed_factor: table(&[
+                ("soul_sand", physics_settings::SOUL_SAND_SPEED),
+                ("honey_block", physics_settings::HONEY_BLOCK_SPEED),
+            ]),
+            block_jump_factor: table(&[("honey_block", 0.5)]),
+        }
+    }
+
+    pub(crate) fn should_move_entity(entity: &EntityPhysicsContext) -> bool {
+        !((entity.state.is_collided_horizontally || entity.state.is_collided_vertically)
+            && !entity.collision_behavior.affected_after_collision)
+    }
+
+    /// original: getBlockPosBelowThatAffectsMyMovement
+    fn get_block_below(entity: &EntityPhysicsContext, world: &impl World) -> Option<Block> {
+        let pos = entity.state.position;
+        world.get_block(&glam::Vec3A::new(pos.x, pos.y - 0.5000001, pos.z))
+    }
+
+    /// original: getBlockSpeedFactor/getBlockJumpFactor
+    /// the block we're in wins, otherwise the one below.
+    fn get_block_factor(
+        &self,
+        table: &HashMap<u32, f32>,
+        entity: &EntityPhysicsContext,
+        world: &impl World,
+    ) -> f32 {
+        let factor_of = |block: Option<Block>| {
+            block
+                .and_then(|b| table.get(&b.b_type).copied())
+                .unwrap_or(1.0)
+        };
+
+        let feet = world.get_block(&entity.state.position);
+        // water and bubble columns never let the block below through.
+        let in_water = feet
+            .as_ref()
+            .is_some_and(|b| b.b_type == self.water_id || b.b_type == self.bubblecolumn_id);
+        let factor = factor_of(feet);
+        if factor != 1.0 || in_water {
+            return factor;
+        }
+        factor_of(Self::get_block_below(entity, world))
+    }
+
+    /// a block none of the special cases (fluids, webs, bubble columns, ladders,
+    /// slime, speed/jump factors) care about. slipperiness is fine.
+    pub(crate) fn is_plain_block(&self, block: &Block) -> bool {
+        let id = block.b_type;
+        !(id == self.water_id
+            || id == self.lava_id
+            || id == self.web_id
+            || id == self.bubblecolumn_id
+            || id == self.slime_block_id
+            || self.water_like.contains(&id)
+            || self.climbable.contains(&id)
+            || self.block_speed_factor.contains_key(&id)
+            || self.block_jump_factor.contains_key(&id)
+            || block.get_properties().waterlogged)
+    }
+
+    fn is_on_climbable(&self, entity: &EntityPhysicsContext, world: &impl World) -> bool {
+        world
+            .get_block(&entity.state.position)
+            .is_some_and(|b| self.climbable.contains(&b.b_type))
+    }
+
+    // ---- fluids ----
+
+    fn get_fluid_amount(&self, block: &Block, fluid: Fluid) -> u8 {
+        let level = block
+            .get_properties()
+            .level
+            .map_or(block.metadata, |level| level as u32);
+        match fluid {
+            Fluid::Water => {
+                if block.b_type == self.water_id {
+                    level_to_amount(level)
+                } else if self.water_like.contains(&block.b_type)
+                    || block.get_properties().waterlogged
+                {
+                    8
+                } else {
+                    0
+                }
+            }
+            Fluid::Lava => {
+                if block.b_type == self.lava_id {
+                    level_to_amount(level)
+                } else {
+                    0
+                }
+            }
+        }
+    }
+
+    fn is_falling_fluid(&self, block: &Block) -> bool {
+        (block.b_type == self.water_id || block.b_type == self.lava_id)
+            && block
+                .get_properties()
+                .level
+                .map_or(block.metadata, |level| level as u32)
+                >= 8
+    }
+
+    /// original: FluidState.getHeight, full if the same fluid is above.
+    fn get_fluid_height(&self, block: &Block, fluid: Fluid, world: &impl World) -> f32 {
+        let above = world.get_block(&(block.position + glam::Vec3A::Y));
+        if above.is_some_and(|b| self.get_fluid_amount(&b, fluid) > 0) {
+            return 1.0;
+        }
+        self.get_fluid_amount(block, fluid) as f32 / 9.0
+    }
+
+    /// original: FlowingFluid.getFlow
+    fn get_flow(&self, block: &Block, fluid: Fluid, world: &impl World) -> glam::Vec3A {
+        let own_height = self.get_fluid_amount(block, fluid) as f32 / 9.0;
+        let other = if fluid == Fluid::Water {
+            Fluid::Lava
+        } else {
+            Fluid::Water
+        };
+        let mut flow = glam::Vec3A::ZERO;
+
+        for [dx, dz] in [[0.0, -1.0], [0.0, 1.0], [-1.0, 0.0], [1.0, 0.0]] {
+            let adj = match world.get_block(&(block.position + glam::Vec3A::new(dx, 0.0, dz))) {
+                Some(adj) => adj,
+                None => continue,
+            };
+            // other fluids don't affect flow.
+            if self.get_fluid_amount(&adj, other) > 0 {
+                continue;
+            }
+
+            let mut adj_height = self.get_fluid_amount(&adj, fluid) as f32 / 9.0;
+            let mut diff = 0.0;
+            if adj_height == 0.0 {
+                if adj.shapes.is_empty() {
+                    let below = world.get_block(&(adj.position - glam::Vec3A::Y));
+                    if let Some(below) = below {
+                        adj_height = self.get_fluid_amount(&below, fluid) as f32 / 9.0;
+                        if adj_height > 0.0 {
+                            diff = own_height - (adj_height - 0.8888889);
+                        }
+                    }
+                }
+            } else {
+                diff = own_height - adj_height;
+            }
+
+            flow.x += dx * diff;
+            flow.z += dz * diff;
+        }
+
+        if self.is_falling_fluid(block) {
+            for [dx, dz] in [[0.0, -1.0], [0.0, 1.0], [-1.0, 0.0], [1.0, 0.0]] {
+                let side = block.position + glam::Vec3A::new(dx, 0.0, dz);
+                let solid =
+                    |pos: glam::Vec3A| world.get_block(&pos).is_some_and(|b| !b.shapes.is_empty());
+                if solid(side) || solid(side + glam::Vec3A::Y) {
+                    flow = flow.normalize_or_zero() + glam::Vec3A::new(0.0, -6.0, 0.0);
+                    break;
+                }
+            }
+        }
+        flow.normalize_or_zero()
+    }
+
+    /// original: updateFluidHeightAndDoFluidPushing
+    /// returns how deep the entity is in the fluid, none if it isn't.
+    fn update_fluid_and_push(
+        &self,
+        entity: &mut EntityPhysicsContext,
+        fluid: Fluid,
+        scale: f32,
+        world: &impl World,
+    ) -> Option<f32> {
+        let bb = entity
+            .get_current_bb_with_pose()
+            .contract(0.001, 0.001, 0.001);
+        let mut depth: f32 = 0.0;
+        let mut in_fluid = false;
+        let mut push = glam::Vec3A::ZERO;
+        let mut pushes = 0;
+
+        let mut cursor = glam::Vec3A::new(bb.min_x.floor(), bb.min_y.floor(), bb.min_z.floor());
+        while cursor.y < bb.max_y.ceil() {
+            cursor.z = bb.min_z.floor();
+            while cursor.z < bb.max_z.ceil() {
+                cursor.x = bb.min_x.floor();
+                while cursor.x < bb.max_x.ceil() {
+                    if let Some(block) = world.get_block(&cursor) {
+                        if self.get_fluid_amount(&block, fluid) > 0 {
+                            let top = cursor.y + self.get_fluid_height(&block, fluid, world);
+                            if top >= bb.min_y {
+                                in_fluid = true;
+                                depth = depth.max(top - bb.min_y);
+                                let mut flow = self.get_flow(&block, fluid, world);
+                                if depth < 0.4 {
+                                    flow *= depth;
+                                }
+                                push += flow;
+                                pushes += 1;
+                            }
+                        }
+                    }
+                    cursor.x += 1.0;
+                }
+                cursor.z += 1.0;
+            }
+            cursor.y += 1.0;
+        }
+
+        if push.length() > 0.0 {
+            push /= pushes as f32;
+            if !entity.is_player() {
+                push = push.normalize();
+            }
+            push *= scale;
+            let vel = entity.state.velocity;
+            if vel.x.abs() < 0.003 && vel.z.abs() < 0.003 && push.length() < 0.0045 {
+                push = push.normalize() * 0.0045;
+            }
+            entity.state.velocity += push;
+        }
+
+        if in_fluid {
+            Some(depth)
+        } else {
+            None
+        }
+    }
+
+    // ---- player-only behavior ----
+
+    /// strafe and forward impulses from the control states.
+    fn player_input(entity: &mut EntityPhysicsContext) -> (f32, f32) {
+        let controls = &entity.state.control_states;
+        // original: xxa = left - right, positive strafe is to the left.
+        let mut strafe = (controls.left as u8 as f32) - (controls.right as u8 as f32);
+        let mut forward = (controls.forward as u8 as f32) - (controls.back as u8 as f32);
+
+        if entity.state.control_states.sneak {
+            strafe *= physics_settings::SNEAK_SPEED;
+            forward *= physics_settings::SNEAK_SPEED;
+            entity.state.control_states.sprint = false;
+        }
+        if entity.state.is_using_item {
+            strafe *= physics_settings::USING_ITEM_SPEED;
+            forward *= physics_settings::USING_ITEM_SPEED;
+            entity.state.control_states.sprint = false;
+        }
+        (strafe, forward)
+    }
+
+    /// original: getFlyingSpeed, sprinting players accelerate more mid-air.
+    fn player_flying_speed(entity: &EntityPhysicsContext) -> f32 {
+        if entity.state.control_states.sprint {
+            0.025999999
+        } else {
+            physics_settings::AIRBORNE_ACCELERATION
+        }
+    }
+
+    /// original: Player.isSuppressingSlidingDownLadder / isSuppressingBounce
+    pub(crate) fn player_is_sneaking(entity: &EntityPhysicsContext) -> bool {
+        entity.is_player() && entity.state.control_states.sneak
+    }
+
+    /// original: Player.maybeBackOffFromEdge
+    fn player_back_off_from_edge(
+        entity: &EntityPhysicsContext,
+        vel: glam::Vec3A,
+        world: &impl World,
+    ) -> glam::Vec3A {
+        if vel.y > 0.0 || !Self::player_is_sneaking(entity) || !entity.state.on_ground {
+            return vel;
+        }
+
+        let bb = entity.get_current_bb_with_pose();
+        let down = -entity.step_height;
+        let step = 0.05;
+        let back_off = |d: f32| {
+            if d.abs() < step {
+                0.0
+            } else if d > 0.0 {
+                d - step
+            } else {
+                d + step
+            }
+        };
+
+        let (mut dx, mut dz) = (vel.x, vel.z);
+        while dx != 0.0 && collision::no_collision(&bb.offset(dx, down, 0.0), world) {
+            dx = back_off(dx);
+        }
+        while dz != 0.0 && collision::no_collision(&bb.offset(0.0, down, dz), world) {
+            dz = back_off(dz);
+        }
+        while dx != 0.0 && dz != 0.0 && collision::no_collision(&bb.offset(dx, down, dz), world) {
+            dx = back_off(dx);
+            dz = back_off(dz);
+        }
+        glam::Vec3A::new(dx, vel.y, dz)
+    }
+
+    /// movement speed attribute, with the sprinting modifier applied client-side.
+    fn get_movement_speed(&self, entity: &EntityPhysicsContext) -> f32 {
+        let mut attribute = entity
+            .state
+            .attributes
+            .get(&self.movement_speed_attribute)
+            .cloned()
+            .unwrap_or_else(|| {
+                PlayerAttribute::create_attribute_value(physics_settings::PLAYER_SPEED)
+            });
+
+        attribute =
+            PlayerAttribute::delete_attribute_modifier(attribute, physics_settings::SPRINTING_UUID);
+        if entity.state.control_states.sprint {
+            attribute = PlayerAttribute::add_attribute_modifier(
+                attribute,
+                PlayerAttributeModifier {
+                    uuid: physics_settings::SPRINTING_UUID.to_string(),
+                    amount: physics_settings::SPRINT_SPEED,
+                    operation: 2,
+                },
+            );
+        }
+        PlayerAttribute::get_attribute_value(attribute)
+    }
+
+    // ---- movement ----
+
+    /// original: LivingEntity.jumpFromGround
+    fn jump_from_ground(&self, entity: &mut EntityPhysicsContext, world: &impl World) {
+        let jump_factor = self.get_block_factor(&self.block_jump_factor, entity, world);
+        entity.state.velocity.y = physics_settings::JUMP_HEIGHT * jump_factor;
+        if entity.state.jump_boost > 0 {
+            entity.state.velocity.y += 0.1 * entity.state.jump_boost as f32;
+        }
+
+        if entity.is_player() && entity.state.control_states.sprint {
+            let yaw = entity.state.rotation.get_vanilla_yaw();
+            entity.state.velocity.x -= yaw.sin() * 0.2;
+            entity.state.velocity.z += yaw.cos() * 0.2;
+        }
+    }
+
+    /// original: Entity.moveRelative
+    fn move_relative(entity: &mut EntityPhysicsContext, speed: f32, strafe: f32, forward: f32) {
+        let len_sq = strafe * strafe + forward * forward;
+        if len_sq < 1.0e-7 {
+            return;
+        }
+        let scale = if len_sq > 1.0 {
+            speed / len_sq.sqrt()
+        } else {
+            speed
+        };
+        let (strafe, forward) = (strafe * scale, forward * scale);
+
+        let yaw = entity.state.rotation.get_vanilla_yaw();
+        let (sin, cos) = yaw.sin_cos();
+        entity.state.velocity.x += strafe * cos - forward * sin;
+        entity.state.velocity.z += forward * cos + strafe * sin;
+    }
+
+    /// whether a blocked move gets another try one step higher.
+    pub(crate) fn can_step_up(
+        entity: &EntityPhysicsContext,
+        vel: glam::Vec3A,
+        collided: glam::Vec3A,
+    ) -> bool {
+        let collided_x = vel.x != collided.x;
+        let collided_y = vel.y != collided.y;
+        let collided_z = vel.z != collided.z;
+        let grounded = entity.state.on_ground || (collided_y && vel.y < 0.0);
+        entity.step_height > 0.0 && grounded && (collided_x || collided_z)
+    }
+
+    /// original: Entity.collide, including stepping up.
+    fn collide(
+        entity: &mut EntityPhysicsContext,
+        vel: glam::Vec3A,
+        world: &impl World,
+    ) -> glam::Vec3A {
+        let bb = entity.get_current_bb_with_pose();
+        let collided = collision::collide_bounding_box(vel, &bb, world);
+
+        if Self::can_step_up(entity, vel, collided) {
+            let horizontal_sq = |v: glam::Vec3A| v.x * v.x + v.z * v.z;
+            let step = entity.step_height;
+
+            let mut stepped =
+                collision::collide_bounding_box(glam::Vec3A::new(vel.x, step, vel.z), &bb, world);
+            let up = collision::collide_bounding_box(
+                glam::Vec3A::new(0.0, step, 0.0),
+                &bb.extend(vel.x, 0.0, vel.z),
+                world,
+            );
+            if up.y < step {
+                let across = collision::collide_bounding_box(
+                    glam::Vec3A::new(vel.x, 0.0, vel.z),
+                    &bb.offset(up.x, up.y, up.z),
+                    world,
+                ) + up;
+                if horizontal_sq(across) > horizontal_sq(stepped) {
+                    stepped = across;
+                }
+            }
+
+            if horizontal_sq(stepped) > horizontal_sq(collided) {
+                let down = collision::collide_bounding_box(
+                    glam::Vec3A::new(0.0, -stepped.y + vel.y, 0.0),
+                    &bb.offset(stepped.x, stepped.y, stepped.z),
+                    world,
+                );
+                let moved = stepped + down;
+                entity.record(TraceStage::StepUp {
+                    candidate: moved,
+                    taken: true,
+                });
+                entity.record(TraceStage::Collision {
+                    requested: vel,
+                    allowed: moved,
+                });
+                entity.emit(PhysicsEvent::SteppedUp { height: moved.y });
+                return moved;
+            }
+            entity.record(TraceStage::StepUp {
+                candidate: stepped,
+                taken: false,
+            });
+        }
+        entity.record(TraceStage::Collision {
+            requested: vel,
+            allowed: collided,
+        });
+        collided
+    }
+
+    /// original: Entity.move
+    fn move_entity(
+        &self,
+        entity: &mut EntityPhysicsContext,
+        mut vel: glam::Vec3A,
+        world: &impl World,
+    ) {
+        let was_in_web = entity.state.is_in_web;
+        if entity.state.is_in_web {
+            vel *= glam::Vec3A::new(0.25, 0.05, 0.25);
+            entity.state.velocity = glam::Vec3A::ZERO;
+            entity.state.is_in_web = false;
+            entity.record(TraceStage::BlockEffect {
+                effect: BlockEffect::Web,
+                velocity: vel,
+            });
+        }
+
+        if entity.is_player() {
+            vel = Self::player_back_off_from_edge(entity, vel, world);
+        }
+
+        let moved = Self::collide(entity, vel, world);
+        self.finish_move(entity, vel, moved, world);
+
+        if entity.state.is_in_web && !was_in_web {
+            // original: makeStuckInBlock
+            entity.state.fall_distance = 0.0;
+            entity.emit(PhysicsEvent::EnteredWeb);
+        }
+    }
+
+    /// second half of Entity.move: apply what `collide` allowed and react to it.
+    pub(crate) fn finish_move(
+        &self,
+        entity: &mut EntityPhysicsContext,
+        vel: glam::Vec3A,
+        moved: glam::Vec3A,
+        world: &impl World,
+    ) {
+        if moved.length_squared() > 1.0e-7 {
+            entity.state.position += moved;
+        }
+
+        let was_on_ground = entity.state.on_ground;
+        let was_collided_horizontally = entity.state.is_collided_horizontally;
+        let collided_x = (vel.x - moved.x).abs() >= 1.0e-5;
+        let collided_z = (vel.z - moved.z).abs() >= 1.0e-5;
+        entity.state.is_collided_horizontally = collided_x || collided_z;
+        entity.state.is_collided_vertically = vel.y != moved.y;
+        entity.state.sneak_collision = entity.state.is_collided_horizontally;
+        entity.state.on_ground = entity.state.is_collided_vertically && vel.y < 0.0;
+        events::track_move(entity, was_on_ground, was_collided_horizontally, moved.y);
+
+        if collided_x {
+            entity.state.velocity.x = 0.0;
+        }
+        if collided_z {
+            entity.state.velocity.z = 0.0;
+        }
+
+        // original: getOnPosLegacy
+        let pos = entity.state.position;
+        let on_block = world.get_block(&glam::Vec3A::new(pos.x, pos.y - 0.2, pos.z));
+        let on_slime = entity.collision_behavior.block_effects
+            && on_block.is_some_and(|b| b.b_type == self.slime_block_id);
+
+        // original: Block.updateEntityAfterFallOn
+        if vel.y != moved.y {
+            if on_slime && !Self::player_is_sneaking(entity) {
+                if entity.state.velocity.y < 0.0 {
+                    let bounciness = if entity.is_living() { 1.0 } else { 0.8 };
+                    entity.state.velocity.y = -entity.state.velocity.y * bounciness;
+                    let velocity = entity.state.velocity.y;
+                    entity.record(TraceStage::BlockEffect {
+                        effect: BlockEffect::SlimeBounce,
+                        velocity: entity.state.velocity,
+                    });
+                    entity.emit(PhysicsEvent::SlimeBounce { velocity });
+                }
+            } else {
+                entity.state.velocity.y = 0.0;
+            }
+        }
+
+        // original: SlimeBlock.stepOn
+        if entity.state.on_ground && on_slime && !Self::player_is_sneaking(entity) {
+            let y = entity.state.velocity.y.abs();
+            if y < 0.1 {
+                let factor = 0.4 + y * 0.2;
+                entity.state.velocity.x *= factor;
+                entity.state.velocity.z *= factor;
+            }
+        }
+
+        self.check_inside_blocks(entity, world);
+
+        if entity.collision_behavior.block_effects {
+            let factor = self.get_block_factor(&self.block_speed_factor, entity, world);
+            entity.state.velocity.x *= factor;
+            entity.state.velocity.z *= factor;
+            if factor != 1.0 {
+                entity.record(TraceStage::BlockEffect {
+                    effect: BlockEffect::SpeedFactor,
+                    velocity: entity.state.velocity,
+                });
+            }
+        }
+    }
+
+    /// original: Entity.checkInsideBlocks, only cobwebs and bubble columns matter here.
+    fn check_inside_blocks(&self, entity: &mut EntityPhysicsContext, world: &impl World) {
+        let bb = entity
+            .get_current_bb_with_pose()
+            .contract(1.0e-7, 1.0e-7, 1.0e-7);
+        let bb_fl = bb.floored();
+        let mut cursor = glam::Vec3A::new(bb_fl.min_x, bb_fl.min_y, bb_fl.min_z);
+
+        while cursor.y <= bb_fl.max_y {
+            cursor.z = bb_fl.min_z;
+            while cursor.z <= bb_fl.max_z {
+                cursor.x = bb_fl.min_x;
+                while cursor.x <= bb_fl.max_x {
+                    if let Some(block) = world.get_block(&cursor) {
+                        if block.b_type == self.web_id {
+                            entity.state.is_in_web = true;
+                        } else if block.b_type == self.bubblecolumn_id {
+                            let down = block.get_properties().drag.unwrap_or(block.metadata == 0);
+                            let above = world.get_block(&(cursor + glam::Vec3A::Y));
+                            let surface = above.is_some_and(|b| self.air_ids.contains(&b.b_type));
+                            let drag = if surface {
+                                physics_settings::BUBBLE_COLUMN_SURFACE_DRAG
+                            } else {
+                                physics_settings::BUBBLE_COLUMN_DRAG
+                            };
+                            let vel = &mut entity.state.velocity;
+                            if down {
+                                vel.y = drag.max_down.max(vel.y - drag.down);
+                            } else {
+                                vel.y = drag.max_up.min(vel.y + drag.up);
+                            }
+                            entity.record(TraceStage::BlockEffect {
+                                effect: BlockEffect::BubbleColumn,
+                                velocity: entity.state.velocity,
+                            });
+                        }
+                    }
+                    cursor.x += 1.0;
+                }
+                cursor.z += 1.0;
+            }
+            cursor.y += 1.0;
+        }
+    }
+
+    /// original: LivingEntity.getFluidFallingAdjustedMovement
+    fn fluid_falling_adjusted(entity: &EntityPhysicsContext, gravity: f32, falling: bool) -> f32 {
+        let y = entity.state.velocity.y;
+        if entity.state.control_states.sprint && entity.is_player() {
+            return y;
+        }
+        if falling && (y - 0.005).abs() >= 0.003 && (y - gravity).abs() < 0.003 {
+            -0.003
+        } else {
+            y - gravity
+        }
+    }
+
+    /// jump out of a liquid when pushing against a wall that's free one step up.
+    fn try_leave_fluid(&self, entity: &mut EntityPhysicsContext, start_y: f32, world: &impl World) {
+        let vel = entity.state.velocity;
+        let pos = entity.state.position;
+        let bb =
+            entity
+                .get_current_bb_with_pose()
+                .offset(vel.x, vel.y + 0.6 - pos.y + start_y, vel.z);
+        if entity.state.is_collided_horizontally
+            && collision::no_collision(&bb, world)
+            && self.get_water_or_lava_in(&bb, world).is_none()
+        {
+            entity.state.velocity.y = physics_settings::OUT_OF_LIQUID_IMPULSE;
+        }
+    }
+
+    /// original: Level.containsAnyLiquid
+    fn get_water_or_lava_in(&self, bb: &AABB, world: &impl World) -> Option<Block> {
+        let bb_fl = bb.floored();
+        let mut cursor = glam::Vec3A::new(bb_fl.min_x, bb_fl.min_y, bb_fl.min_z);
+        while cursor.y < bb.max_y.ceil() {
+            cursor.z = bb_fl.min_z;
+            while cursor.z < bb.max_z.ceil() {
+                cursor.x = bb_fl.min_x;
+                while cursor.x < bb.max_x.ceil() {
+                    if let Some(block) = world.get_block(&cursor) {
+                        if self.get_fluid_amount(&block, Fluid::Water) > 0
+                            || self.get_fluid_amount(&block, Fluid::Lava) > 0
+                        {
+                            return Some(block);
+                        }
+                    }
+                    cursor.x += 1.0;
+                }
+                cursor.z += 1.0;
+            }
+            cursor.y += 1.0;
+        }
+        None
+    }
+
+    pub(crate) fn get_gravity_multiplier(entity: &EntityPhysicsContext) -> f32 {
+        if entity.state.velocity.y <= 0.0 && entity.state.slow_falling > 0 {
+            physics_settings::SLOW_FALLING
+        } else {
+            1.0
+        }
+    }
+
+    /// travel on land/in air, up to moving: friction, acceleration and ladders.
+    /// returns the horizontal inertia and whether the entity is on a climbable.
+    pub(crate) fn accelerate_in_air(
+        &self,
+        entity: &mut EntityPhysicsContext,
+        strafe: f32,
+        forward: f32,
+        world: &impl World,
+    ) -> (f32, bool) {
+        let slipperiness = Self::get_block_below(entity, world)
+            .and_then(|b| self.block_slipperiness.get(&b.b_type).copied())
+            .unwrap_or(physics_settings::DEFAULT_SLIPPERINESS);
+        let inertia = if entity.state.on_ground {
+            slipperiness * physics_settings::AIRBORNE_INERTIA
+        } else {
+            physics_settings::AIRBORNE_INERTIA
+        };
+
+        // original: getFrictionInfluencedSpeed
+        let acceleration = if entity.state.on_ground {
+            self.get_movement_speed(entity)
+                * (0.21600002 / (slipperiness * slipperiness * slipperiness))
+        } else if entity.is_player() {
+            Self::player_flying_speed(entity)
+        } else {
+            physics_settings::AIRBORNE_ACCELERATION
+        };
+
+        entity.record(TraceStage::Friction {
+            inertia,
+            acceleration,
+        });
+        Self::move_relative(entity, acceleration, strafe, forward);
+        entity.record(TraceStage::Heading {
+            velocity: entity.state.velocity,
+        });
+
+        // original: handleOnClimbable
+        let on_climbable =
+            entity.collision_behavior.block_effects && self.is_on_climbable(entity, world);
+        if on_climbable {
+            let vel = &mut entity.state.velocity;
+            vel.x = vel.x.clamp(
+                -physics_settings::LADDER_MAX_SPEED,
+                physics_settings::LADDER_MAX_SPEED,
+            );
+            vel.z = vel.z.clamp(
+                -physics_settings::LADDER_MAX_SPEED,
+                physics_settings::LADDER_MAX_SPEED,
+            );
+            vel.y = vel.y.max(-physics_settings::LADDER_MAX_SPEED);
+            if vel.y < 0.0 && Self::player_is_sneaking(entity) {
+                entity.state.velocity.y = 0.0;
+            }
+        }
+        (inertia, on_climbable)
+    }
+
+    /// gravity (or levitation) and air drag, after moving.
+    pub(crate) fn apply_air_drag(
+        entity: &mut EntityPhysicsContext,
+        inertia: f32,
+        gravity_multiplier: f32,
+    ) {
+        let vel = &mut entity.state.velocity;
+        if entity.gravity_then_drag {
+            if entity.state.levitation > 0 {
+                vel.y += (0.05 * entity.state.levitation as f32 - vel.y) * 0.2;
+            } else {
+                vel.y -= entity.gravity * gravity_multiplier;
+            }
+            vel.y *= entity.airdrag;
+        } else {
+            vel.y *= entity.airdrag;
+            if entity.state.levitation > 0 {
+                vel.y += (0.05 * entity.state.levitation as f32 - vel.y) * 0.2;
+            } else {
+                vel.y -= entity.gravity * gravity_multiplier;
+            }
+        }
+        vel.x *= inertia;
+        vel.z *= inertia;
+        entity.record(TraceStage::Drag {
+            velocity: entity.state.velocity,
+        });
+    }
+
+    /// original: LivingEntity.travel
+    fn travel(
+        &self,
+        entity: &mut EntityPhysicsContext,
+        strafe: f32,
+        forward: f32,
+        lava_depth: Option<f32>,
+        world: &impl World,
+    ) {
+        let falling = entity.state.velocity.y <= 0.0;
+        let gravity_multiplier = Self::get_gravity_multiplier(entity);
+        let jumping = entity.use_controls && entity.state.control_states.jump;
+
+        if entity.state.is_in_water {
+            let start_y = entity.state.position.y;
+            // original: isSprinting() ? 0.9 : getWaterSlowDown()
+            let mut inertia = if entity.state.control_states.sprint {
+                0.9
+            } else {
+                entity.water_inertia
+            };
+            let mut acceleration = physics_settings::LIQUID_ACCELERATION;
+            let mut strider = entity.state.depth_strider.min(3) as f32;
+            if !entity.state.on_ground {
+                strider *= 0.5;
+            }
+            if strider > 0.0 {
+                inertia += (0.54600006 - inertia) * strider / 3.0;
+                acceleration += (self.get_movement_speed(entity) - acceleration) * strider / 3.0;
+            }
+            if entity.state.dolphins_grace > 0 {
+                inertia = 0.96;
+            }
+
+            entity.record(TraceStage::Friction {
+                inertia,
+                acceleration,
+            });
+            Self::move_relative(entity, acceleration, strafe, forward);
+            entity.record(TraceStage::Heading {
+                velocity: entity.state.velocity,
+            });
+            self.move_entity(entity, entity.state.velocity, world);
+
+            if entity.state.is_collided_horizontally && self.is_on_climbable(entity, world) {
+                entity.state.velocity.y = physics_settings::LADDER_CLIMB_SPEED;
+                entity.emit(PhysicsEvent::ClimbedLadder);
+            }
+            entity.state.velocity *=
+                glam::Vec3A::new(inertia, physics_settings::WATER_INERTIA, inertia);
+            entity.state.velocity.y = Self::fluid_falling_adjusted(
+                entity,
+                entity.water_gravity * gravity_multiplier,
+                falling,
+            );
+            entity.record(TraceStage::Drag {
+                velocity: entity.state.velocity,
+            });
+
+            self.try_leave_fluid(entity, start_y, world);
+        } else if entity.state.is_in_lava {
+            let start_y = entity.state.position.y;
+            entity.record(TraceStage::Friction {
+                inertia: entity.lava_inertia,
+                acceleration: physics_settings::LIQUID_ACCELERATION,
+            });
+            Self::move_relative(
+                entity,
+                physics_settings::LIQUID_ACCELERATION,
+                strafe,
+                forward,
+            );
+            entity.record(TraceStage::Heading {
+                velocity: entity.state.velocity,
+            });
+            self.move_entity(entity, entity.state.velocity, world);
+
+            // original: getFluidJumpThreshold
+            if lava_depth.unwrap_or(0.0) <= 0.4 {
+                entity.state.velocity *=
+                    glam::Vec3A::new(entity.lava_inertia, 0.8, entity.lava_inertia);
+                // vanilla uses gravity / 16 here too, which is what water_gravity is.
+                entity.state.velocity.y = Self::fluid_falling_adjusted(
+                    entity,
+                    entity.water_gravity * gravity_multiplier,
+                    falling,
+                );
+            } else {
+                entity.state.velocity *= entity.lava_inertia;
+            }
+            entity.state.velocity.y -= entity.lava_gravity * gravity_multiplier;
+            entity.record(TraceStage::Drag {
+                velocity: entity.state.velocity,
+            });
+
+            self.try_leave_fluid(entity, start_y, world);
+        } else {
+            let (inertia, on_climbable) = self.accelerate_in_air(entity, strafe, forward, world);
+            self.move_entity(entity, entity.state.velocity, world);
+
+            if (entity.state.is_collided_horizontally || jumping) && on_climbable {
+                entity.state.velocity.y = physics_settings::LADDER_CLIMB_SPEED;
+                entity.emit(PhysicsEvent::ClimbedLadder);
+            }
+            Self::apply_air_drag(entity, inertia, gravity_multiplier);
+        }
+    }
+
+    /// original: LivingEntity.aiStep (+ Entity.baseTick's fluid update).
+    fn tick_entity(&self, entity: &mut EntityPhysicsContext, world: &impl World) {
+        if !Self::should_move_entity(entity) {
+            entity.state.velocity = glam::Vec3A::ZERO;
+            return;
+        }
+
+        let (strafe, forward, lava_depth) = self.begin_tick(entity, world);
+        self.travel(entity, strafe, forward, lava_depth, world);
+        Self::update_below_world(entity, world);
+    }
+
+    /// aiStep up to travel: fluids, jumping, negligible velocity and inputs.
+    /// returns strafe, forward and the lava depth.
+    pub(crate) fn begin_tick(
+        &self,
+        entity: &mut EntityPhysicsContext,
+        world: &impl World,
+    ) -> (f32, f32, Option<f32>) {
+        let (was_in_water, was_in_lava) = (entity.state.is_in_water, entity.state.is_in_lava);
+        let water_depth = self.update_fluid_and_push(entity, Fluid::Water, 0.014, world);
+        let lava_depth = self.update_fluid_and_push(entity, Fluid::Lava, 0.0023333333, world);
+        entity.state.is_in_water = water_depth.is_some();
+        entity.state.is_in_lava = lava_depth.is_some();
+        events::track_fluids(entity, was_in_water, was_in_lava);
+        entity.record(TraceStage::Fluids {
+            in_water: entity.state.is_in_water,
+            in_lava: entity.state.is_in_lava,
+            velocity: entity.state.velocity,
+        });
+
+        if entity.state.jump_ticks > 0 {
+            entity.state.jump_ticks -= 1;
+        }
+
+        let vel = &mut entity.state.velocity;
+        if vel.x.abs() < physics_settings::NEGLIGEABLE_VELOCITY {
+            vel.x = 0.0;
+        }
+        if vel.y.abs() < physics_settings::NEGLIGEABLE_VELOCITY {
+            vel.y = 0.0;
+        }
+        if vel.z.abs() < physics_settings::NEGLIGEABLE_VELOCITY {
+            vel.z = 0.0;
+        }
+
+        let (mut strafe, mut forward) = (0.0, 0.0);
+        if entity.use_controls {
+            if entity.state.control_states.jump || entity.state.jump_queued {
+                // original: getFluidJumpThreshold, eye height below 0.4 can't swim up.
+                let threshold = if entity.get_height() * 0.85 < 0.4 {
+                    0.0
+                } else {
+                    0.4
+                };
+                let depth = if entity.state.is_in_lava {
+                    lava_depth.unwrap_or(0.0)
+                } else {
+                    water_depth.unwrap_or(0.0)
+                };
+                let swimming = entity.state.is_in_water && depth > 0.0;
+                let shallow = entity.state.on_ground && depth <= threshold;
+
+                if swimming && !shallow {
+                    // original: jumpInLiquid
+                    entity.state.velocity.y += 0.04;
+                } else if entity.state.is_in_lava && !shallow {
+                    entity.state.velocity.y += 0.04;
+                } else if (entity.state.on_ground || (swimming && depth <= threshold))
+                    && entity.state.jump_ticks == 0
+                {
+                    self.jump_from_ground(entity, world);
+                    entity.state.jump_ticks = physics_settings::AUTO_JUMP_COOLDOWN;
+                }
+                entity.record(TraceStage::Jump {
+                    velocity: entity.state.velocity,
+                });
+            } else {
+                entity.state.jump_ticks = 0;
+            }
+            entity.state.jump_queued = false;
+
+            if entity.is_player() {
+                (strafe, forward) = Self::player_input(entity);
+            }
+            strafe *= 0.98;
+            forward *= 0.98;
+        }
+        entity.record(TraceStage::Input { strafe, forward });
+
+        (strafe, forward, lava_depth)
+    }
+
+    pub(crate) fn update_below_world(entity: &mut EntityPhysicsContext, world: &impl World) {
+        entity.state.is_below_world = world.get_min_y().is_some_and(|min_y| {
+            entity.state.position.y < min_y - physics_settings::BELOW_WORLD_DEPTH
+        });
+    }
+}
 
-}
\ No newline at end of file
+impl Simulator for GenSimulator {
+    fn tick(&self, ctx: &mut EntityPhysicsContext, world: &impl World) {
+        self.tick_entity(ctx, world);
+    }
+}
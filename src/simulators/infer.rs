@@ -0,0 +1,128 @@
+use crate::{
+    calc::angle::Rotation,
+    states::{physics_context::EntityPhysicsContext, EntityState},
+};
+
+use super::{inputs::InputCandidate, Simulator, World};
+
+/// how far (position plus velocity error) a prediction may be from the observation.
+pub const DEFAULT_TOLERANCE: f32 = 0.01;
+
+/// Where an entity was seen, and how fast it was going.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Observation {
+    pub position: glam::Vec3A,
+    pub velocity: glam::Vec3A,
+}
+
+/// An input that explains an observed move.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InferredInput {
+    pub input: InputCandidate,
+    pub rotation: Rotation,
+    /// position plus velocity distance to the observation.
+    pub error: f32,
+}
+
+/// Reconstructs what an entity was pressing from two consecutive observations.
+///
+/// Yaw isn't enumerated: each candidate is simulated once at the context's yaw, then
+/// turned so its horizontal push lines up with the observed one and simulated again.
+pub struct InputInference<S: Simulator> {
+    simulator: S,
+    tolerance: f32,
+}
+
+impl<S: Simulator> InputInference<S> {
+    pub fn new(simulator: S) -> Self {
+        Self {
+            simulator,
+            tolerance: DEFAULT_TOLERANCE,
+        }
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn get_simulator(&self) -> &S {
+        &self.simulator
+    }
+
+    pub fn get_tolerance(&self) -> f32 {
+        self.tolerance
+    }
+
+    /// every input within tolerance of going from `from` to `to` in one tick, best first.
+    /// the rest of the state (on_ground, fluids, effects...) and the yaw used when the
+    /// input doesn't move horizontally come from `ctx`, which is left as it was.
+    pub fn infer(
+        &self,
+        ctx: &mut EntityPhysicsContext,
+        from: Observation,
+        to: Observation,
+        world: &impl World,
+    ) -> Vec<InferredInput> {
+        let previous = ctx.state.clone();
+        let mut start = previous.clone();
+        start.position = from.position;
+        start.velocity = from.velocity;
+        let base = start.rotation;
+
+        let error = |state: &EntityState| {
+            state.position.distance(to.position) + state.velocity.distance(to.velocity)
+        };
+        let horizontal = |v: glam::Vec3A| glam::Vec2::new(v.x, v.z);
+
+        let idle = self.run(ctx, &start, InputCandidate::default(), base, world);
+        let wanted = horizontal(to.position - idle.position);
+
+        let mut results = vec![];
+        for input in InputCandidate::all() {
+            let probe = self.run(ctx, &start, input, base, world);
+            let mut best = (error(&probe), base);
+
+            let got = horizontal(probe.position - idle.position);
+            if got.length_squared() > 1.0e-12 && wanted.length_squared() > 1.0e-12 {
+                // the push turns with the entity, so turning by the yaw between the two
+                // directions lines it up.
+                let facing =
+                    |v: glam::Vec2| Rotation::from_direction(glam::Vec3A::new(v.x, 0.0, v.y));
+                let turn = facing(wanted).yaw - facing(got).yaw;
+                let rotation = Rotation::new(base.yaw + turn, base.pitch);
+                let state = self.run(ctx, &start, input, rotation, world);
+                if error(&state) < best.0 {
+                    best = (error(&state), rotation);
+                }
+            }
+
+            if best.0 <= self.tolerance {
+                results.push(InferredInput {
+                    input,
+                    rotation: best.1,
+                    error: best.0,
+                });
+            }
+        }
+        ctx.state = previous;
+
+        results.sort_by(|a, b| a.error.total_cmp(&b.error));
+        results
+    }
+
+    fn run(
+        &self,
+        ctx: &mut EntityPhysicsContext,
+        start: &EntityState,
+        input: InputCandidate,
+        rotation: Rotation,
+        world: &impl World,
+    ) -> EntityState {
+        ctx.state = start.clone();
+        ctx.state.rotation = rotation;
+        input.apply(ctx);
+        self.simulator.tick(ctx, world);
+        ctx.state.clone()
+    }
+}
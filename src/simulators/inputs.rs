@@ -0,0 +1,47 @@
+use crate::states::{physics_context::EntityPhysicsContext, player_context::ControlStateHandler};
+
+/// One combination of keys a player could have held during a tick.
+/// rotation isn't part of it, that's reported by the client.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InputCandidate {
+    pub control_states: ControlStateHandler,
+    pub using_item: bool,
+}
+
+impl InputCandidate {
+    /// every distinct candidate: the 8 directions and standing still, each with and without
+    /// jump, sneak and item use, plus sprinting wherever it's possible (forward held, not
+    /// sneaking, not using an item), 78 in total.
+    pub fn all() -> impl Iterator<Item = InputCandidate> {
+        let axis = [-1i8, 0, 1];
+        axis.into_iter().flat_map(move |forward| {
+            axis.into_iter().flat_map(move |strafe| {
+                (0..16u8).filter_map(move |bits| {
+                    let (jump, sneak, using_item, sprint) =
+                        (bits & 1 != 0, bits & 2 != 0, bits & 4 != 0, bits & 8 != 0);
+                    if sprint && (forward != 1 || sneak || using_item) {
+                        return None;
+                    }
+                    Some(InputCandidate {
+                        control_states: ControlStateHandler {
+                            forward: forward == 1,
+                            back: forward == -1,
+                            left: strafe == -1,
+                            right: strafe == 1,
+                            jump,
+                            sprint,
+                            sneak,
+                        },
+                        using_item,
+                    })
+                })
+            })
+        })
+    }
+
+    /// sets up the entity for the next tick, leaving rotation alone.
+    pub fn apply(&self, ctx: &mut EntityPhysicsContext) {
+        ctx.state.control_states = self.control_states;
+        ctx.state.is_using_item = self.using_item;
+    }
+}
@@ -0,0 +1,370 @@
+use std::fmt::Write;
+
+use crate::{
+    blocks::shape_registry::ShapeRegistry,
+    calc::angle::Rotation,
+    states::player_context::{ControlStateHandler, TickInput},
+};
+
+use super::{
+    rollout::{rollout, start_player, Goal},
+    Block, Simulator, World,
+};
+
+/// Block ids the generated worlds are built from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct JumpBlocks {
+    pub air: u32,
+    pub stone: u32,
+    pub ice: u32,
+    pub soul_sand: u32,
+    pub slime_block: u32,
+}
+
+impl JumpBlocks {
+    /// none if the registry is missing one of them.
+    pub fn from_registry(registry: &ShapeRegistry) -> Option<Self> {
+        Some(Self {
+            air: registry.get_block_id("air")?,
+            stone: registry.get_block_id("stone")?,
+            ice: registry.get_block_id("ice")?,
+            soul_sand: registry.get_block_id("soul_sand")?,
+            slime_block: registry.get_block_id("slime_block")?,
+        })
+    }
+}
+
+/// What the run-up (and the block jumped from) is made of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Surface {
+    Normal,
+    Ice,
+    SoulSand,
+    Slime,
+}
+
+impl Surface {
+    pub const ALL: [Surface; 4] = [Self::Normal, Self::Ice, Self::SoulSand, Self::Slime];
+
+    fn get_name(self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Ice => "ice",
+            Self::SoulSand => "soul_sand",
+            Self::Slime => "slime",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Takeoff {
+    /// from the center of the edge block.
+    Standing,
+    /// after sprinting (or walking) `run_up_blocks` towards the target.
+    Running,
+}
+
+/// Which jumps to simulate. Offsets are in blocks, relative to the edge block, forward is +z.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JumpTableConfig {
+    pub max_forward: i32,
+    pub max_side: i32,
+    pub min_dy: i32,
+    pub max_dy: i32,
+    pub run_up_blocks: f32,
+    pub surfaces: Vec<Surface>,
+    /// headroom above the run-up in blocks, 2 is a head hitter. none means open sky.
+    pub ceilings: Vec<Option<u8>>,
+    /// per attempt, run-up included.
+    pub max_ticks: u32,
+}
+
+impl Default for JumpTableConfig {
+    fn default() -> Self {
+        Self {
+            max_forward: 5,
+            max_side: 3,
+            min_dy: -3,
+            max_dy: 1,
+            run_up_blocks: 8.0,
+            surfaces: Surface::ALL.to_vec(),
+            ceilings: vec![None, Some(2), Some(3)],
+            max_ticks: 80,
+        }
+    }
+}
+
+/// One reachable landing and the quickest way there.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct JumpEntry {
+    pub takeoff: Takeoff,
+    pub surface: Surface,
+    pub ceiling: Option<u8>,
+    /// feet block landed in, relative to the edge block.
+    pub offset: (i32, i32, i32),
+    /// feet position the inputs start from, relative to the edge block's corner.
+    /// the middle of its top face when standing, `run_up_blocks` behind it when running.
+    pub start: glam::Vec3A,
+    pub sprint: bool,
+    /// level, facing the landing block's center.
+    pub rotation: Rotation,
+    /// tick jump is first held on, counted from the start.
+    pub jump_tick: u32,
+    /// ticks until standing on the landing block.
+    pub ticks: u32,
+}
+
+impl JumpEntry {
+    /// the inputs to feed the simulator from `start`, one per tick: forward all along, jump from `jump_tick`.
+    pub fn get_inputs(&self) -> Vec<TickInput> {
+        (0..self.ticks)
+            .map(|tick| {
+                TickInput::new(
+                    ControlStateHandler {
+                        forward: true,
+                        jump: tick >= self.jump_tick,
+                        sprint: self.sprint,
+                        ..Default::default()
+                    },
+                    self.rotation,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Every jump the generator found to work.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct JumpTable {
+    entries: Vec<JumpEntry>,
+}
+
+impl JumpTable {
+    pub fn get_entries(&self) -> &[JumpEntry] {
+        &self.entries
+    }
+
+    pub fn find(
+        &self,
+        takeoff: Takeoff,
+        surface: Surface,
+        ceiling: Option<u8>,
+        offset: (i32, i32, i32),
+    ) -> Option<&JumpEntry> {
+        self.entries.iter().find(|e| {
+            e.takeoff == takeoff
+                && e.surface == surface
+                && e.ceiling == ceiling
+                && e.offset == offset
+        })
+    }
+
+    /// one line per entry, with a header.
+    pub fn to_csv(&self) -> String {
+        let mut out =
+            "takeoff,surface,ceiling,dx,dy,dz,start_x,start_z,sprint,yaw,jump_tick,ticks\n"
+                .to_string();
+        for e in self.entries.iter() {
+            let takeoff = match e.takeoff {
+                Takeoff::Standing => "standing",
+                Takeoff::Running => "running",
+            };
+            let ceiling = e.ceiling.map(|c| c.to_string()).unwrap_or_default();
+            let _ = writeln!(
+                out,
+                "{},{},{},{},{},{},{},{},{},{},{},{}",
+                takeoff,
+                e.surface.get_name(),
+                ceiling,
+                e.offset.0,
+                e.offset.1,
+                e.offset.2,
+                e.start.x,
+                e.start.z,
+                e.sprint,
+                e.rotation.yaw,
+                e.jump_tick,
+                e.ticks
+            );
+        }
+        out
+    }
+}
+
+/// the run-up half-plane (z <= 0, floor at y = 0), an optional ceiling over it,
+/// and a single landing block.
+struct JumpWorld {
+    blocks: JumpBlocks,
+    surface: u32,
+    ceiling: Option<f32>,
+    target: Option<glam::Vec3A>,
+}
+
+impl World for JumpWorld {
+    fn get_block(&self, pos: &glam::Vec3A) -> Option<Block> {
+        let pos = pos.floor();
+        let b_type = if Some(pos) == self.target {
+            self.blocks.stone
+        } else if pos.z <= 0.0 && pos.y == -1.0 {
+            self.surface
+        } else if pos.z <= 0.0 && Some(pos.y) == self.ceiling {
+            self.blocks.stone
+        } else {
+            self.blocks.air
+        };
+        let (bounding_box, shapes) = if b_type == self.blocks.air {
+            ("empty", vec![])
+        } else {
+            ("block", vec![[0.0, 0.0, 0.0, 1.0, 1.0, 1.0]])
+        };
+        Some(Block::test_new(
+            bounding_box.to_string(),
+            0,
+            b_type,
+            pos,
+            shapes,
+        ))
+    }
+}
+
+/// Simulates every configured jump and keeps the ones that land.
+///
+/// The simulator should come from the same registry as the blocks,
+/// otherwise ice and friends are just stone to it.
+pub struct JumpTableGenerator<S: Simulator> {
+    simulator: S,
+    blocks: JumpBlocks,
+    config: JumpTableConfig,
+}
+
+impl<S: Simulator> JumpTableGenerator<S> {
+    pub fn new(simulator: S, blocks: JumpBlocks) -> Self {
+        Self {
+            simulator,
+            blocks,
+            config: JumpTableConfig::default(),
+        }
+    }
+
+    pub fn with_config(mut self, config: JumpTableConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn get_config(&self) -> &JumpTableConfig {
+        &self.config
+    }
+
+    pub fn generate(&self) -> JumpTable {
+        let mut entries = vec![];
+        for takeoff in [Takeoff::Standing, Takeoff::Running] {
+            for &surface in self.config.surfaces.iter() {
+                for &ceiling in self.config.ceilings.iter() {
+                    for dz in 1..=self.config.max_forward {
+                        for dx in -self.config.max_side..=self.config.max_side {
+                            for dy in self.config.min_dy..=self.config.max_dy {
+                                let offset = (dx, dy, dz);
+                                if let Some(entry) =
+                                    self.try_offset(takeoff, surface, ceiling, offset)
+                                {
+                                    entries.push(entry);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        JumpTable { entries }
+    }
+
+    fn try_offset(
+        &self,
+        takeoff: Takeoff,
+        surface: Surface,
+        ceiling: Option<u8>,
+        offset: (i32, i32, i32),
+    ) -> Option<JumpEntry> {
+        let (dx, dy, dz) = offset;
+        let target = glam::Vec3A::new(dx as f32, dy as f32, dz as f32);
+        let rotation = Rotation::from_direction(glam::Vec3A::new(dx as f32, 0.0, dz as f32));
+        let mut world = JumpWorld {
+            blocks: self.blocks,
+            surface: match surface {
+                Surface::Normal => self.blocks.stone,
+                Surface::Ice => self.blocks.ice,
+                Surface::SoulSand => self.blocks.soul_sand,
+                Surface::Slime => self.blocks.slime_block,
+            },
+            ceiling: ceiling.map(|c| c as f32),
+            target: None,
+        };
+
+        let start = self.get_start(takeoff, rotation);
+        // forward all along, jump from `jump_tick`. ticks until `goal`.
+        let run = |world: &JumpWorld, sprint: bool, jump_tick: Option<u32>, goal, limit| {
+            let mut ctx = start_player(start, true);
+            let inputs = rollout(
+                &self.simulator,
+                &mut ctx,
+                goal,
+                self.config.max_ticks,
+                limit,
+                world,
+                |tick, _, _| {
+                    TickInput::new(
+                        ControlStateHandler {
+                            forward: true,
+                            jump: jump_tick.is_some_and(|j| tick >= j),
+                            sprint,
+                            ..Default::default()
+                        },
+                        rotation,
+                    )
+                },
+            );
+            inputs.map(|inputs| inputs.len() as u32)
+        };
+
+        let mut best: Option<JumpEntry> = None;
+        for sprint in [true, false] {
+            // jumping only makes sense in the last few ticks before walking off.
+            let leave = match run(&world, sprint, None, Goal::LeaveGround, usize::MAX) {
+                // the tick it left on.
+                Some(ticks) => ticks - 1,
+                None => continue,
+            };
+
+            world.target = Some(target - glam::Vec3A::Y);
+            for jump_tick in leave.saturating_sub(5)..=leave {
+                let limit = best.map_or(usize::MAX, |best| best.ticks as usize);
+                if let Some(ticks) =
+                    run(&world, sprint, Some(jump_tick), Goal::Land(target), limit)
+                {
+                    best = Some(JumpEntry {
+                        takeoff,
+                        surface,
+                        ceiling,
+                        offset,
+                        start,
+                        sprint,
+                        rotation,
+                        jump_tick,
+                        ticks,
+                    });
+                }
+            }
+            world.target = None;
+        }
+        best
+    }
+
+    fn get_start(&self, takeoff: Takeoff, rotation: Rotation) -> glam::Vec3A {
+        let back = match takeoff {
+            Takeoff::Standing => 0.0,
+            Takeoff::Running => self.config.run_up_blocks,
+        };
+        // that far behind the edge block, facing it.
+        glam::Vec3A::new(0.5, 0.0, 0.5) - rotation.get_direction() * back
+    }
+}
@@ -0,0 +1,535 @@
+use glam::Vec4;
+
+use crate::{
+    calc::aabb::AABB,
+    states::{physics_context::EntityPhysicsContext, player_context::ControlStateHandler},
+};
+
+use super::{gen_simulator::GenSimulator, Simulator, World};
+
+/// entities per vector.
+pub const LANES: usize = 4;
+
+/// never collides with anything, pads lanes that ran out of shapes.
+const NO_SHAPE: AABB = AABB {
+    min_x: f32::MAX,
+    min_y: f32::MAX,
+    min_z: f32::MAX,
+    max_x: f32::MAX,
+    max_y: f32::MAX,
+    max_z: f32::MAX,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LaneStats {
+    /// entity ticks that went through the vectorized path.
+    pub simd: u64,
+    /// entity ticks that fell back to `GenSimulator::tick`.
+    pub scalar: u64,
+}
+
+/// what the shared scalar stages touch before we know a lane can stay vectorized.
+/// events and trace stages they record get dropped too, the scalar tick records them again.
+#[derive(Clone, Copy)]
+struct Saved {
+    velocity: glam::Vec3A,
+    control_states: ControlStateHandler,
+    jump_ticks: u8,
+    jump_queued: bool,
+    is_in_water: bool,
+    is_in_lava: bool,
+    fall_distance: f32,
+    events: usize,
+    stages: usize,
+}
+
+impl Saved {
+    fn save(entity: &EntityPhysicsContext) -> Self {
+        Self {
+            velocity: entity.state.velocity,
+            control_states: entity.state.control_states,
+            jump_ticks: entity.state.jump_ticks,
+            jump_queued: entity.state.jump_queued,
+            is_in_water: entity.state.is_in_water,
+            is_in_lava: entity.state.is_in_lava,
+            fall_distance: entity.state.fall_distance,
+            events: entity.events.as_ref().map_or(0, |events| events.len()),
+            stages: entity.trace.as_ref().map_or(0, |trace| trace.stages.len()),
+        }
+    }
+
+    fn restore(&self, entity: &mut EntityPhysicsContext) {
+        entity.state.velocity = self.velocity;
+        entity.state.control_states = self.control_states;
+        entity.state.jump_ticks = self.jump_ticks;
+        entity.state.jump_queued = self.jump_queued;
+        entity.state.is_in_water = self.is_in_water;
+        entity.state.is_in_lava = self.is_in_lava;
+        entity.state.fall_distance = self.fall_distance;
+        if let Some(events) = entity.events.as_mut() {
+            events.truncate(self.events);
+        }
+        if let Some(trace) = entity.trace.as_mut() {
+            trace.stages.truncate(self.stages);
+        }
+    }
+}
+
+/// one AABB per lane.
+#[derive(Clone, Copy)]
+struct BoxLanes {
+    min_x: Vec4,
+    min_y: Vec4,
+    min_z: Vec4,
+    max_x: Vec4,
+    max_y: Vec4,
+    max_z: Vec4,
+}
+
+impl BoxLanes {
+    fn gather(boxes: [&AABB; LANES]) -> Self {
+        let get =
+            |f: fn(&AABB) -> f32| Vec4::new(f(boxes[0]), f(boxes[1]), f(boxes[2]), f(boxes[3]));
+        Self {
+            min_x: get(|bb| bb.min_x),
+            min_y: get(|bb| bb.min_y),
+            min_z: get(|bb| bb.min_z),
+            max_x: get(|bb| bb.max_x),
+            max_y: get(|bb| bb.max_y),
+            max_z: get(|bb| bb.max_z),
+        }
+    }
+
+    fn offset_x(mut self, d: Vec4) -> Self {
+        self.min_x += d;
+        self.max_x += d;
+        self
+    }
+
+    fn offset_y(mut self, d: Vec4) -> Self {
+        self.min_y += d;
+        self.max_y += d;
+        self
+    }
+
+    fn offset_z(mut self, d: Vec4) -> Self {
+        self.min_z += d;
+        self.max_z += d;
+        self
+    }
+
+    /// AABB::compute_offset_x, with `self` as the shapes.
+    fn compute_offset_x(&self, bb: &BoxLanes, d: Vec4) -> Vec4 {
+        let overlap = bb.max_y.cmpgt(self.min_y)
+            & bb.min_y.cmplt(self.max_y)
+            & bb.max_z.cmpgt(self.min_z)
+            & bb.min_z.cmplt(self.max_z);
+        let pos = overlap & d.cmpgt(Vec4::ZERO) & bb.max_x.cmple(self.min_x);
+        let neg = overlap & d.cmplt(Vec4::ZERO) & bb.min_x.cmpge(self.max_x);
+        let d = Vec4::select(pos, (self.min_x - bb.max_x).min(d), d);
+        Vec4::select(neg, (self.max_x - bb.min_x).max(d), d)
+    }
+
+    /// AABB::compute_offset_y, with `self` as the shapes.
+    fn compute_offset_y(&self, bb: &BoxLanes, d: Vec4) -> Vec4 {
+        let overlap = bb.max_x.cmpgt(self.min_x)
+            & bb.min_x.cmplt(self.max_x)
+            & bb.max_z.cmpgt(self.min_z)
+            & bb.min_z.cmplt(self.max_z);
+        let pos = overlap & d.cmpgt(Vec4::ZERO) & bb.max_y.cmple(self.min_y);
+        let neg = overlap & d.cmplt(Vec4::ZERO) & bb.min_y.cmpge(self.max_y);
+        let d = Vec4::select(pos, (self.min_y - bb.max_y).min(d), d);
+        Vec4::select(neg, (self.max_y - bb.min_y).max(d), d)
+    }
+
+    /// AABB::compute_offset_z, with `self` as the shapes.
+    fn compute_offset_z(&self, bb: &BoxLanes, d: Vec4) -> Vec4 {
+        let overlap = bb.max_x.cmpgt(self.min_x)
+            & bb.min_x.cmplt(self.max_x)
+            & bb.max_y.cmpgt(self.min_y)
+            & bb.min_y.cmplt(self.max_y);
+        let pos = overlap & d.cmpgt(Vec4::ZERO) & bb.max_z.cmple(self.min_z);
+        let neg = overlap & d.cmplt(Vec4::ZERO) & bb.min_z.cmpge(self.max_z);
+        let d = Vec4::select(pos, (self.min_z - bb.max_z).min(d), d);
+        Vec4::select(neg, (self.max_z - bb.min_z).max(d), d)
+    }
+}
+
+/// the struct-of-arrays part. every field has one slot per loaded entity,
+/// padded up to a multiple of LANES before running the vector passes.
+#[derive(Default)]
+struct Lanes {
+    index: Vec<usize>,
+    saved: Vec<Saved>,
+    bb: Vec<AABB>,
+    shapes: Vec<Vec<AABB>>,
+    vel_x: Vec<f32>,
+    vel_y: Vec<f32>,
+    vel_z: Vec<f32>,
+    moved_x: Vec<f32>,
+    moved_y: Vec<f32>,
+    moved_z: Vec<f32>,
+    gravity: Vec<f32>,
+    airdrag: Vec<f32>,
+    inertia: Vec<f32>,
+    /// 1.0 if gravity_then_drag, 0.0 otherwise.
+    gravity_first: Vec<f32>,
+    active: Vec<bool>,
+}
+
+impl Lanes {
+    fn clear(&mut self) {
+        self.index.clear();
+        self.saved.clear();
+        self.bb.clear();
+        // keep the inner allocations around.
+        for shapes in self.shapes.iter_mut() {
+            shapes.clear();
+        }
+        self.vel_x.clear();
+        self.vel_y.clear();
+        self.vel_z.clear();
+        self.moved_x.clear();
+        self.moved_y.clear();
+        self.moved_z.clear();
+        self.gravity.clear();
+        self.airdrag.clear();
+        self.inertia.clear();
+        self.gravity_first.clear();
+        self.active.clear();
+    }
+
+    fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// grabs a (cleared) shape list for the next lane.
+    fn next_shapes(&mut self) -> &mut Vec<AABB> {
+        let lane = self.len();
+        if self.shapes.len() <= lane {
+            self.shapes.push(vec![]);
+        }
+        &mut self.shapes[lane]
+    }
+
+    fn push(
+        &mut self,
+        index: usize,
+        saved: Saved,
+        entity: &EntityPhysicsContext,
+        inertia: f32,
+        gravity_multiplier: f32,
+    ) {
+        let vel = entity.state.velocity;
+        self.index.push(index);
+        self.saved.push(saved);
+        self.bb.push(entity.get_current_bb_with_pose());
+        self.vel_x.push(vel.x);
+        self.vel_y.push(vel.y);
+        self.vel_z.push(vel.z);
+        self.gravity.push(entity.gravity * gravity_multiplier);
+        self.airdrag.push(entity.airdrag);
+        self.inertia.push(inertia);
+        self.gravity_first
+            .push(if entity.gravity_then_drag { 1.0 } else { 0.0 });
+        self.active.push(true);
+    }
+
+    /// fills the last vector up with lanes that don't move or collide.
+    fn pad(&mut self) {
+        let padded = self.len().div_ceil(LANES) * LANES;
+        for field in [
+            &mut self.vel_x,
+            &mut self.vel_y,
+            &mut self.vel_z,
+            &mut self.gravity,
+            &mut self.airdrag,
+            &mut self.inertia,
+            &mut self.gravity_first,
+        ] {
+            field.resize(padded, 0.0);
+        }
+        self.bb.resize(padded, NO_SHAPE);
+        self.moved_x.resize(padded, 0.0);
+        self.moved_y.resize(padded, 0.0);
+        self.moved_z.resize(padded, 0.0);
+        if self.shapes.len() < padded {
+            self.shapes.resize(padded, vec![]);
+        }
+        for shapes in self.shapes[self.index.len()..padded].iter_mut() {
+            shapes.clear();
+        }
+    }
+
+    /// collision::collide_with_shapes, LANES entities at a time.
+    fn collide(&mut self) {
+        for start in (0..self.bb.len()).step_by(LANES) {
+            let end = start + LANES;
+            let bb = BoxLanes::gather([
+                &self.bb[start],
+                &self.bb[start + 1],
+                &self.bb[start + 2],
+                &self.bb[start + 3],
+            ]);
+            let shapes = &self.shapes[start..end];
+            let count = shapes.iter().map(|s| s.len()).max().unwrap_or(0);
+            let shape_lanes: Vec<BoxLanes> = (0..count)
+                .map(|i| {
+                    let get = |lane: usize| shapes[lane].get(i).unwrap_or(&NO_SHAPE);
+                    BoxLanes::gather([get(0), get(1), get(2), get(3)])
+                })
+                .collect();
+
+            let vel_x = Vec4::from_slice(&self.vel_x[start..end]);
+            let vel_y = Vec4::from_slice(&self.vel_y[start..end]);
+            let vel_z = Vec4::from_slice(&self.vel_z[start..end]);
+
+            let mut dy = vel_y;
+            for shape in shape_lanes.iter() {
+                dy = shape.compute_offset_y(&bb, dy);
+            }
+            let bb = bb.offset_y(dy);
+
+            // lanes disagree on the axis order, so do both and pick per lane.
+            let z_first = vel_x.abs().cmplt(vel_z.abs());
+
+            let mut dz_a = vel_z;
+            for shape in shape_lanes.iter() {
+                dz_a = shape.compute_offset_z(&bb, dz_a);
+            }
+            let bb_a = bb.offset_z(dz_a);
+            let mut dx_a = vel_x;
+            for shape in shape_lanes.iter() {
+                dx_a = shape.compute_offset_x(&bb_a, dx_a);
+            }
+
+            let mut dx_b = vel_x;
+            for shape in shape_lanes.iter() {
+                dx_b = shape.compute_offset_x(&bb, dx_b);
+            }
+            let bb_b = bb.offset_x(dx_b);
+            let mut dz_b = vel_z;
+            for shape in shape_lanes.iter() {
+                dz_b = shape.compute_offset_z(&bb_b, dz_b);
+            }
+
+            Vec4::select(z_first, dx_a, dx_b).write_to_slice(&mut self.moved_x[start..end]);
+            dy.write_to_slice(&mut self.moved_y[start..end]);
+            Vec4::select(z_first, dz_a, dz_b).write_to_slice(&mut self.moved_z[start..end]);
+        }
+    }
+
+    /// GenSimulator::apply_air_drag without levitation, LANES entities at a time.
+    fn apply_air_drag(&mut self) {
+        for start in (0..self.vel_x.len()).step_by(LANES) {
+            let end = start + LANES;
+            let gravity = Vec4::from_slice(&self.gravity[start..end]);
+            let airdrag = Vec4::from_slice(&self.airdrag[start..end]);
+            let inertia = Vec4::from_slice(&self.inertia[start..end]);
+            let gravity_first = Vec4::from_slice(&self.gravity_first[start..end]).cmpne(Vec4::ZERO);
+
+            let vel_y = Vec4::from_slice(&self.vel_y[start..end]);
+            let vel_y = Vec4::select(
+                gravity_first,
+                (vel_y - gravity) * airdrag,
+                vel_y * airdrag - gravity,
+            );
+            vel_y.write_to_slice(&mut self.vel_y[start..end]);
+            (Vec4::from_slice(&self.vel_x[start..end]) * inertia)
+                .write_to_slice(&mut self.vel_x[start..end]);
+            (Vec4::from_slice(&self.vel_z[start..end]) * inertia)
+                .write_to_slice(&mut self.vel_z[start..end]);
+        }
+    }
+}
+
+/// Struct-of-arrays stepping for many GenSimulator entities at once.
+///
+/// Collision offsets, gravity and drag run over LANES entities per vector,
+/// the rest (inputs, friction, landing...) reuses GenSimulator's own stages,
+/// so results are identical to calling `tick` on every entity.
+///
+/// Only the common case is vectorized: walking or falling through plain blocks.
+/// Liquids, ladders, webs, slime, speed/jump factor blocks, levitation,
+/// sneaking players and stepping up fall back to `GenSimulator::tick`.
+pub struct LaneSimulator {
+    simulator: GenSimulator,
+    lanes: Lanes,
+    stats: LaneStats,
+}
+
+impl LaneSimulator {
+    pub fn new(simulator: GenSimulator) -> Self {
+        Self {
+            simulator,
+            lanes: Lanes::default(),
+            stats: LaneStats::default(),
+        }
+    }
+
+    pub fn get_simulator(&self) -> &GenSimulator {
+        &self.simulator
+    }
+
+    pub fn get_stats(&self) -> LaneStats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = LaneStats::default();
+    }
+
+    /// one tick for every entity.
+    pub fn tick_all(&mut self, ctxs: &mut [EntityPhysicsContext], world: &impl World) {
+        self.lanes.clear();
+        for (index, ctx) in ctxs.iter_mut().enumerate() {
+            if !self.try_load(index, ctx, world) {
+                self.simulator.tick(ctx, world);
+                self.stats.scalar += 1;
+            }
+        }
+        let loaded = self.lanes.len();
+        self.lanes.pad();
+        self.lanes.collide();
+
+        for lane in 0..loaded {
+            let ctx = &mut ctxs[self.lanes.index[lane]];
+            let vel = glam::Vec3A::new(
+                self.lanes.vel_x[lane],
+                self.lanes.vel_y[lane],
+                self.lanes.vel_z[lane],
+            );
+            let moved = glam::Vec3A::new(
+                self.lanes.moved_x[lane],
+                self.lanes.moved_y[lane],
+                self.lanes.moved_z[lane],
+            );
+
+            if GenSimulator::can_step_up(ctx, vel, moved) {
+                self.lanes.saved[lane].restore(ctx);
+                self.lanes.active[lane] = false;
+                self.simulator.tick(ctx, world);
+                self.stats.scalar += 1;
+                continue;
+            }
+
+            self.simulator.finish_move(ctx, vel, moved, world);
+            let vel = ctx.state.velocity;
+            self.lanes.vel_x[lane] = vel.x;
+            self.lanes.vel_y[lane] = vel.y;
+            self.lanes.vel_z[lane] = vel.z;
+        }
+
+        self.lanes.apply_air_drag();
+
+        for lane in 0..loaded {
+            if !self.lanes.active[lane] {
+                continue;
+            }
+            let ctx = &mut ctxs[self.lanes.index[lane]];
+            ctx.state.velocity = glam::Vec3A::new(
+                self.lanes.vel_x[lane],
+                self.lanes.vel_y[lane],
+                self.lanes.vel_z[lane],
+            );
+            GenSimulator::update_below_world(ctx, world);
+            self.stats.simd += 1;
+        }
+    }
+
+    /// `ticks` ticks for every entity.
+    pub fn simulate_ticks_all(
+        &mut self,
+        ctxs: &mut [EntityPhysicsContext],
+        ticks: u32,
+        world: &impl World,
+    ) {
+        for _ in 0..ticks {
+            self.tick_all(ctxs, world);
+        }
+    }
+
+    /// runs the scalar stages up to moving and queues the entity into a lane.
+    /// false (with the entity untouched) if it needs the scalar path.
+    fn try_load(
+        &mut self,
+        index: usize,
+        entity: &mut EntityPhysicsContext,
+        world: &impl World,
+    ) -> bool {
+        if !GenSimulator::should_move_entity(entity)
+            || entity.state.is_in_web
+            || entity.state.levitation > 0
+            || GenSimulator::player_is_sneaking(entity)
+        {
+            return false;
+        }
+
+        let saved = Saved::save(entity);
+        let (strafe, forward, _) = self.simulator.begin_tick(entity, world);
+        if entity.state.is_in_water || entity.state.is_in_lava {
+            saved.restore(entity);
+            return false;
+        }
+
+        let gravity_multiplier = GenSimulator::get_gravity_multiplier(entity);
+        let (inertia, on_climbable) = self
+            .simulator
+            .accelerate_in_air(entity, strafe, forward, world);
+        if on_climbable || !self.gather_shapes(entity, world) {
+            saved.restore(entity);
+            return false;
+        }
+
+        self.lanes
+            .push(index, saved, entity, inertia, gravity_multiplier);
+        true
+    }
+
+    /// collects the shapes the entity can hit this tick into the next lane,
+    /// false if anything in reach isn't a plain block.
+    ///
+    /// scans the same blocks as collision::get_collision_bbs, whose bottom layer
+    /// also covers the blocks below the entity that friction and landing read.
+    fn gather_shapes(&mut self, entity: &EntityPhysicsContext, world: &impl World) -> bool {
+        let vel = entity.state.velocity;
+        let bb = entity.get_current_bb_with_pose();
+        let query_bb = bb.extend(vel.x, vel.y, vel.z);
+        let q_bb_fl = query_bb.floored();
+        let simulator = &self.simulator;
+        let shapes = self.lanes.next_shapes();
+        shapes.clear();
+
+        let mut cursor = glam::Vec3A::new(q_bb_fl.min_x, q_bb_fl.min_y - 1.0, q_bb_fl.min_z);
+        while cursor.y <= q_bb_fl.max_y {
+            cursor.z = q_bb_fl.min_z;
+            while cursor.z <= q_bb_fl.max_z {
+                cursor.x = q_bb_fl.min_x;
+                while cursor.x <= q_bb_fl.max_x {
+                    if let Some(block) = world.get_block(&cursor) {
+                        if !simulator.is_plain_block(&block) {
+                            return false;
+                        }
+                        let b_pos = block.get_shape_origin();
+                        for shape in block.shapes.iter() {
+                            shapes.push(
+                                AABB::new(
+                                    shape[0], shape[1], shape[2], shape[3], shape[4], shape[5],
+                                )
+                                .offset(b_pos.x, b_pos.y, b_pos.z),
+                            );
+                        }
+                    }
+                    cursor.x += 1.0;
+                }
+                cursor.z += 1.0;
+            }
+            cursor.y += 1.0;
+        }
+
+        if let Some(border) = world.get_world_border() {
+            shapes.extend(border.get_collision_bbs(&bb, &query_bb));
+        }
+        true
+    }
+}
@@ -1,10 +1,35 @@
-use crate::states::physics_context::EntityPhysicsContext;
+use crate::{states::physics_context::EntityPhysicsContext, world::border::WorldBorder};
 
+use self::{
+    events::{EventListener, PhysicsEvent},
+    trace::{TickTrace, TraceStage},
+};
+
+pub use crate::blocks::{offset::BlockOffset, properties::BlockProps};
+
+#[cfg(feature = "parallel")]
+pub mod batch;
+pub mod collision;
+pub mod events;
+pub mod follow;
+pub mod infer;
+pub mod inputs;
 pub mod prismarine_simulator;
+pub mod raycast;
+pub mod reach;
+pub mod reconcile;
+mod rollout;
 pub mod gen_simulator;
+pub mod jump_table;
+pub mod lanes;
+pub mod optimizer;
+pub mod pathfinder;
+pub mod trace;
+pub mod trajectory;
+pub mod validator;
 
 /// Temporary
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct Block {
     // original = type: u32
     pub(crate) bounding_box: String,
@@ -12,6 +37,8 @@ pub struct Block {
     pub(crate) b_type: u32,
     pub(crate) position: glam::Vec3A,
     pub(crate) shapes: Vec<[f32; 6]>,
+    pub(crate) properties: BlockProps,
+    pub(crate) offset: BlockOffset,
 }
 
 impl Block {
@@ -28,27 +55,168 @@ impl Block {
             b_type,
             position,
             shapes,
+            properties: BlockProps::default(),
+            offset: BlockOffset::NONE,
         }
     }
-}
 
-#[derive(Default)]
-pub struct BlockProps {
-    waterlogged: bool,
+    /// state properties reported by the world.
+    pub fn with_properties(mut self, properties: BlockProps) -> Self {
+        self.properties = properties;
+        self
+    }
+
+    pub fn with_offset(mut self, offset: BlockOffset) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn get_type(&self) -> u32 {
+        self.b_type
+    }
+
+    pub fn get_position(&self) -> glam::Vec3A {
+        self.position
+    }
+
+    pub fn get_shapes(&self) -> &[[f32; 6]] {
+        &self.shapes
+    }
+
+    /// where this block's shapes actually start, position-hash offset included.
+    pub fn get_shape_origin(&self) -> glam::Vec3A {
+        self.position + self.offset.get_offset(&self.position)
+    }
 }
 
 impl Block {
-    pub fn get_properties(&self) -> BlockProps {
-        BlockProps::default()
+    pub fn get_properties(&self) -> &BlockProps {
+        &self.properties
     }
 }
 
 pub trait World {
     fn get_block(&self, pos: &glam::Vec3A) -> Option<Block>;
+
+    /// entities inside collide with it, none by default.
+    fn get_world_border(&self) -> Option<WorldBorder> {
+        None
+    }
+
+    /// minimum build height, used for the void check. none by default.
+    fn get_min_y(&self) -> Option<f32> {
+        None
+    }
 }
 
 
+/// What changed over one tick, so callers don't have to diff flags themselves.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TickSummary {
+    pub landed: bool,
+    pub left_ground: bool,
+    pub collided_horizontally: bool,
+    pub collided_vertically: bool,
+    pub entered_water: bool,
+    pub left_water: bool,
+    pub entered_lava: bool,
+    pub left_lava: bool,
+}
+
 pub trait Simulator {
+    /// advances `ctx` by one tick, in place.
+    fn tick(&self, ctx: &mut EntityPhysicsContext, world: &impl World);
 
-    fn simulate(&self, ctx: EntityPhysicsContext, world: &impl World) -> EntityPhysicsContext;
-}
\ No newline at end of file
+    /// one tick, summarized.
+    fn step(&self, ctx: &mut EntityPhysicsContext, world: &impl World) -> TickSummary {
+        let was_on_ground = ctx.state.on_ground;
+        let was_in_water = ctx.state.is_in_water;
+        let was_in_lava = ctx.state.is_in_lava;
+
+        self.tick(ctx, world);
+
+        let state = &ctx.state;
+        TickSummary {
+            landed: !was_on_ground && state.on_ground,
+            left_ground: was_on_ground && !state.on_ground,
+            collided_horizontally: state.is_collided_horizontally,
+            collided_vertically: state.is_collided_vertically,
+            entered_water: !was_in_water && state.is_in_water,
+            left_water: was_in_water && !state.is_in_water,
+            entered_lava: !was_in_lava && state.is_in_lava,
+            left_lava: was_in_lava && !state.is_in_lava,
+        }
+    }
+
+    /// one tick, returning what happened during it.
+    fn tick_with_events(
+        &self,
+        ctx: &mut EntityPhysicsContext,
+        world: &impl World,
+    ) -> Vec<PhysicsEvent> {
+        ctx.events = Some(vec![]);
+        self.tick(ctx, world);
+        ctx.events.take().unwrap_or_default()
+    }
+
+    /// one tick, then hands every event to `listener` in order.
+    fn tick_with_listener(
+        &self,
+        ctx: &mut EntityPhysicsContext,
+        world: &impl World,
+        listener: &mut impl EventListener,
+    ) {
+        for event in self.tick_with_events(ctx, world) {
+            listener.on_event(ctx, &event);
+        }
+    }
+
+    /// one tick, recording every intermediate stage. slower, meant for debugging.
+    fn tick_with_trace(&self, ctx: &mut EntityPhysicsContext, world: &impl World) -> TickTrace {
+        ctx.trace = Some(TickTrace::default());
+        ctx.record(TraceStage::Start {
+            position: ctx.state.position,
+            velocity: ctx.state.velocity,
+        });
+        self.tick(ctx, world);
+        ctx.record(TraceStage::End {
+            position: ctx.state.position,
+            velocity: ctx.state.velocity,
+            on_ground: ctx.state.on_ground,
+        });
+        ctx.trace.take().unwrap_or_default()
+    }
+
+    /// by-value version of `tick`.
+    fn simulate(&self, mut ctx: EntityPhysicsContext, world: &impl World) -> EntityPhysicsContext {
+        self.tick(&mut ctx, world);
+        ctx
+    }
+
+    fn simulate_ticks(
+        &self,
+        ctx: &mut EntityPhysicsContext,
+        ticks: u32,
+        world: &impl World,
+    ) -> Vec<TickSummary> {
+        (0..ticks).map(|_| self.step(ctx, world)).collect()
+    }
+
+    /// steps until `predicate` holds, returning how many ticks that took.
+    /// none if it still didn't after `max_ticks`.
+    fn simulate_until(
+        &self,
+        ctx: &mut EntityPhysicsContext,
+        max_ticks: u32,
+        world: &impl World,
+        mut predicate: impl FnMut(&EntityPhysicsContext, &TickSummary) -> bool,
+    ) -> Option<u32> {
+        for tick in 1..=max_ticks {
+            let summary = self.step(ctx, world);
+            if predicate(ctx, &summary) {
+                return Some(tick);
+            }
+        }
+        None
+    }
+}
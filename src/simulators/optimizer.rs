@@ -0,0 +1,201 @@
+use std::collections::HashSet;
+
+use crate::{
+    calc::angle::Rotation,
+    states::{physics_context::EntityPhysicsContext, player_context::TickInput, EntityState},
+};
+
+use super::{inputs::InputCandidate, Simulator, World};
+
+/// how many sequences are kept after every tick.
+pub const DEFAULT_BEAM_WIDTH: usize = 32;
+/// added to the error when the goal wants to end on ground (or not) and it doesn't.
+const GROUND_PENALTY: f32 = 1.0;
+
+/// The state to end up as close as possible to.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Goal {
+    pub position: Option<glam::Vec3A>,
+    pub velocity: Option<glam::Vec3A>,
+    pub on_ground: Option<bool>,
+}
+
+impl Goal {
+    pub fn position(position: glam::Vec3A) -> Self {
+        Self {
+            position: Some(position),
+            ..Default::default()
+        }
+    }
+
+    pub fn velocity(velocity: glam::Vec3A) -> Self {
+        Self {
+            velocity: Some(velocity),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_position(mut self, position: glam::Vec3A) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    pub fn with_velocity(mut self, velocity: glam::Vec3A) -> Self {
+        self.velocity = Some(velocity);
+        self
+    }
+
+    pub fn with_on_ground(mut self, on_ground: bool) -> Self {
+        self.on_ground = Some(on_ground);
+        self
+    }
+
+    /// position plus velocity distance, plus a penalty for the wrong on_ground.
+    pub fn get_error(&self, state: &EntityState) -> f32 {
+        let mut error = 0.0;
+        if let Some(position) = self.position {
+            error += state.position.distance(position);
+        }
+        if let Some(velocity) = self.velocity {
+            error += state.velocity.distance(velocity);
+        }
+        if self.on_ground.is_some_and(|g| g != state.on_ground) {
+            error += GROUND_PENALTY;
+        }
+        error
+    }
+}
+
+/// The best sequence found.
+#[derive(Clone)]
+pub struct OptimizedInputs {
+    /// one per tick.
+    pub inputs: Vec<TickInput>,
+    /// where they end up.
+    pub state: EntityState,
+    pub error: f32,
+}
+
+/// one sequence in the beam.
+#[derive(Clone)]
+struct Beam {
+    inputs: Vec<TickInput>,
+    state: EntityState,
+    error: f32,
+}
+
+/// Searches for the inputs that bring an entity closest to a `Goal` after N ticks.
+///
+/// Beam search: every tick each kept sequence is extended with every candidate
+/// (at every yaw offset), simulated, and only the `beam_width` closest to the goal
+/// survive. Sequences ending in (almost) the same state are only kept once.
+pub struct InputOptimizer<S: Simulator> {
+    simulator: S,
+    beam_width: usize,
+    candidates: Vec<InputCandidate>,
+    yaw_offsets: Vec<f32>,
+}
+
+impl<S: Simulator> InputOptimizer<S> {
+    /// searches every `InputCandidate` that doesn't use an item, at the context's yaw.
+    pub fn new(simulator: S) -> Self {
+        Self {
+            simulator,
+            beam_width: DEFAULT_BEAM_WIDTH,
+            candidates: InputCandidate::all().filter(|c| !c.using_item).collect(),
+            yaw_offsets: vec![0.0],
+        }
+    }
+
+    pub fn with_beam_width(mut self, beam_width: usize) -> Self {
+        self.beam_width = beam_width.max(1);
+        self
+    }
+
+    /// item use isn't part of `TickInput`, the result won't say which ticks used one.
+    pub fn with_candidates(mut self, candidates: Vec<InputCandidate>) -> Self {
+        self.candidates = candidates;
+        self
+    }
+
+    /// yaws tried every tick, relative to the context's.
+    pub fn with_yaw_offsets(mut self, yaw_offsets: Vec<f32>) -> Self {
+        self.yaw_offsets = yaw_offsets;
+        self
+    }
+
+    pub fn get_simulator(&self) -> &S {
+        &self.simulator
+    }
+
+    pub fn get_beam_width(&self) -> usize {
+        self.beam_width
+    }
+
+    /// best `ticks` long sequence from `ctx`'s state, which is left as it was.
+    pub fn optimize(
+        &self,
+        ctx: &mut EntityPhysicsContext,
+        goal: Goal,
+        ticks: u32,
+        world: &impl World,
+    ) -> OptimizedInputs {
+        let start = ctx.state.clone();
+        let rotation = start.rotation;
+        let mut beams = vec![Beam {
+            inputs: vec![],
+            error: goal.get_error(&start),
+            state: start.clone(),
+        }];
+
+        for _ in 0..ticks {
+            let mut next = vec![];
+            for beam in beams.iter() {
+                for &offset in self.yaw_offsets.iter() {
+                    for candidate in self.candidates.iter() {
+                        ctx.state = beam.state.clone();
+                        ctx.state.rotation = Rotation::new(rotation.yaw + offset, rotation.pitch);
+                        candidate.apply(ctx);
+                        let input = TickInput::from_state(&ctx.state);
+                        self.simulator.tick(ctx, world);
+
+                        let mut inputs = beam.inputs.clone();
+                        inputs.push(input);
+                        next.push(Beam {
+                            inputs,
+                            error: goal.get_error(&ctx.state),
+                            state: ctx.state.clone(),
+                        });
+                    }
+                }
+            }
+            next.sort_by(|a, b| a.error.total_cmp(&b.error));
+
+            let mut seen = HashSet::new();
+            next.retain(|beam| seen.insert(Self::key(&beam.state)));
+            next.truncate(self.beam_width);
+            if next.is_empty() {
+                break;
+            }
+            beams = next;
+        }
+        ctx.state = start;
+
+        let best = beams.swap_remove(0);
+        OptimizedInputs {
+            inputs: best.inputs,
+            state: best.state,
+            error: best.error,
+        }
+    }
+
+    /// states this close are treated as the same.
+    fn key(state: &EntityState) -> ([i32; 6], bool) {
+        let q = |v: f32| (v * 1.0e4).round() as i32;
+        let (p, v) = (state.position, state.velocity);
+        (
+            [q(p.x), q(p.y), q(p.z), q(v.x), q(v.y), q(v.z)],
+            state.on_ground,
+        )
+    }
+}
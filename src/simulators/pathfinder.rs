@@ -0,0 +1,589 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+
+use crate::{
+    blocks::shape_registry::ShapeRegistry,
+    calc::{aabb::AABB, angle::Rotation},
+    states::{
+        context_builder::EntityPhysicsContextBuilder,
+        player_context::{ControlStateHandler, TickInput},
+    },
+};
+
+use super::{
+    collision,
+    reach::{Approach, ReachabilitySearch},
+    rollout::{rollout, start_player, Goal},
+    Simulator, World,
+};
+
+/// most nodes expanded before giving up.
+pub const DEFAULT_MAX_NODES: usize = 10_000;
+/// longest a single move is simulated for.
+pub const DEFAULT_MAX_MOVE_TICKS: u32 = 40;
+/// blocks per tick no move beats, keeps the heuristic from overestimating.
+const MAX_SPEED: f32 = 0.4;
+
+const HORIZONTAL: [(i32, i32); 8] = [
+    (0, -1),
+    (0, 1),
+    (-1, 0),
+    (1, 0),
+    (-1, -1),
+    (-1, 1),
+    (1, -1),
+    (1, 1),
+];
+
+/// Block ids the pathfinder treats specially, everything else is just its shapes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PathBlocks {
+    pub water: HashSet<u32>,
+    pub climbable: HashSet<u32>,
+}
+
+impl PathBlocks {
+    pub fn from_registry(registry: &ShapeRegistry) -> Self {
+        let ids = |names: &[&str]| -> HashSet<u32> {
+            names
+                .iter()
+                .filter_map(|name| registry.get_block_id(name))
+                .collect()
+        };
+        Self {
+            water: ids(&["water", "bubble_column"]),
+            climbable: ids(&[
+                "ladder",
+                "vine",
+                "scaffolding",
+                "weeping_vines",
+                "weeping_vines_plant",
+                "twisting_vines",
+                "twisting_vines_plant",
+                "cave_vines",
+                "cave_vines_plant",
+            ]),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MoveKind {
+    /// to a neighbouring block on the same level, diagonals included.
+    Walk,
+    /// jump up one block.
+    Ascend,
+    /// walk off an edge, falling up to 3 blocks.
+    Descend,
+    /// jump over a 1 to 3 block gap, landing level or one lower.
+    SprintJump,
+    Swim,
+    /// up or down a ladder (or vine...).
+    Climb,
+}
+
+/// One move between two nodes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Segment {
+    pub from: glam::IVec3,
+    pub to: glam::IVec3,
+    pub kind: MoveKind,
+    /// what the simulator was fed, one per tick, starting centered in `from`.
+    pub inputs: Vec<TickInput>,
+}
+
+impl Segment {
+    pub fn get_ticks(&self) -> u32 {
+        self.inputs.len() as u32
+    }
+
+    /// every key held at some point during the move.
+    pub fn get_control_states(&self) -> ControlStateHandler {
+        self.inputs
+            .iter()
+            .fold(ControlStateHandler::default(), |acc, input| {
+                let c = input.control_states;
+                ControlStateHandler {
+                    forward: acc.forward || c.forward,
+                    back: acc.back || c.back,
+                    left: acc.left || c.left,
+                    right: acc.right || c.right,
+                    jump: acc.jump || c.jump,
+                    sprint: acc.sprint || c.sprint,
+                    sneak: acc.sneak || c.sneak,
+                }
+            })
+    }
+
+    /// level, facing `to`. the default for straight up/down moves.
+    pub fn get_rotation(&self) -> Rotation {
+        self.inputs
+            .first()
+            .map_or(Rotation::default(), |input| input.rotation)
+    }
+
+    /// first tick jump is held on, if ever.
+    pub fn get_jump_tick(&self) -> Option<u32> {
+        self.inputs
+            .iter()
+            .position(|input| input.control_states.jump)
+            .map(|tick| tick as u32)
+    }
+}
+
+/// The moves from `start` to the goal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Path {
+    pub start: glam::IVec3,
+    pub segments: Vec<Segment>,
+}
+
+impl Path {
+    /// `start`, then where every segment ends.
+    pub fn get_waypoints(&self) -> Vec<glam::IVec3> {
+        std::iter::once(self.start)
+            .chain(self.segments.iter().map(|s| s.to))
+            .collect()
+    }
+
+    /// total cost, in ticks.
+    pub fn get_ticks(&self) -> u32 {
+        self.segments.iter().map(|s| s.get_ticks()).sum()
+    }
+}
+
+/// one way of attempting a move.
+#[derive(Clone, Copy, Default)]
+struct Attempt {
+    forward: bool,
+    sprint: bool,
+    sneak: bool,
+    jump_from: Option<u32>,
+    /// once airborne, let go of forward and hold back.
+    brake: bool,
+}
+
+impl Attempt {
+    fn forward(sprint: bool) -> Self {
+        Self {
+            forward: true,
+            sprint,
+            ..Default::default()
+        }
+    }
+
+    fn jump(mut self, jump_from: u32) -> Self {
+        self.jump_from = Some(jump_from);
+        self
+    }
+
+    fn brake(mut self) -> Self {
+        self.brake = true;
+        self
+    }
+}
+
+/// open set entry, ordered so the heap pops the lowest estimate first.
+struct Open {
+    estimate: f32,
+    node: glam::IVec3,
+}
+
+impl PartialEq for Open {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Open {}
+
+impl PartialOrd for Open {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Open {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+/// A* over block positions (the block the feet are in).
+///
+/// Candidate moves come from collision checks against the world's shapes, then every
+/// one is simulated from standing centered in its start block (gaps go through a full
+/// `ReachabilitySearch`). Moves the simulator can't complete are dropped, the others
+/// cost the ticks they took.
+pub struct Pathfinder<S: Simulator> {
+    reach: ReachabilitySearch<S>,
+    blocks: PathBlocks,
+    max_nodes: usize,
+    max_move_ticks: u32,
+    half_width: f32,
+    height: f32,
+}
+
+impl<S: Simulator> Pathfinder<S> {
+    pub fn new(simulator: S, blocks: PathBlocks) -> Self {
+        let player = EntityPhysicsContextBuilder::player()
+            .build()
+            .expect("the player preset is valid");
+        Self {
+            reach: ReachabilitySearch::new(simulator).with_max_air_ticks(DEFAULT_MAX_MOVE_TICKS),
+            blocks,
+            max_nodes: DEFAULT_MAX_NODES,
+            max_move_ticks: DEFAULT_MAX_MOVE_TICKS,
+            half_width: player.get_width() / 2.0,
+            height: player.get_height(),
+        }
+    }
+
+    pub fn with_max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = max_nodes;
+        self
+    }
+
+    pub fn with_max_move_ticks(mut self, max_move_ticks: u32) -> Self {
+        self.max_move_ticks = max_move_ticks;
+        self.reach = self.reach.with_max_air_ticks(max_move_ticks);
+        self
+    }
+
+    pub fn get_simulator(&self) -> &S {
+        self.reach.get_simulator()
+    }
+
+    pub fn get_blocks(&self) -> &PathBlocks {
+        &self.blocks
+    }
+
+    /// quickest path (in ticks) from `start` to `goal`.
+    /// none if there isn't one, or it wasn't found within `max_nodes` expansions.
+    pub fn find(&self, start: glam::IVec3, goal: glam::IVec3, world: &impl World) -> Option<Path> {
+        let heuristic = |node: glam::IVec3| (goal - node).as_vec3().length() / MAX_SPEED;
+
+        let mut open = BinaryHeap::new();
+        let mut costs: HashMap<glam::IVec3, u32> = HashMap::new();
+        let mut came_from: HashMap<glam::IVec3, Segment> = HashMap::new();
+        let mut closed: HashSet<glam::IVec3> = HashSet::new();
+        open.push(Open {
+            estimate: heuristic(start),
+            node: start,
+        });
+        costs.insert(start, 0);
+
+        while let Some(Open { node, .. }) = open.pop() {
+            if node == goal {
+                return Some(Self::rebuild(start, goal, came_from));
+            }
+            if !closed.insert(node) {
+                continue;
+            }
+            if closed.len() > self.max_nodes {
+                return None;
+            }
+
+            let cost = costs[&node];
+            for segment in self.neighbours(node, world) {
+                let to = segment.to;
+                let new_cost = cost + segment.get_ticks();
+                let better = match costs.get(&to) {
+                    Some(&old) => new_cost < old,
+                    None => true,
+                };
+                if better && !closed.contains(&to) {
+                    costs.insert(to, new_cost);
+                    came_from.insert(to, segment);
+                    open.push(Open {
+                        estimate: new_cost as f32 + heuristic(to),
+                        node: to,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    fn rebuild(
+        start: glam::IVec3,
+        goal: glam::IVec3,
+        mut came_from: HashMap<glam::IVec3, Segment>,
+    ) -> Path {
+        let mut segments = vec![];
+        let mut node = goal;
+        while node != start {
+            let segment = came_from
+                .remove(&node)
+                .expect("every reached node has a segment");
+            node = segment.from;
+            segments.push(segment);
+        }
+        segments.reverse();
+        Path { start, segments }
+    }
+
+    /// every candidate move from `node` that the simulator manages.
+    fn neighbours(&self, node: glam::IVec3, world: &impl World) -> Vec<Segment> {
+        self.candidates(node, world)
+            .into_iter()
+            .filter_map(|(to, kind)| self.measure(node, to, kind, world))
+            .collect()
+    }
+
+    /// moves that look possible from the block shapes alone.
+    fn candidates(&self, node: glam::IVec3, world: &impl World) -> Vec<(glam::IVec3, MoveKind)> {
+        let up = glam::IVec3::Y;
+        let in_water = self.is_water(node, world);
+        let head_room = self.fits(node + up, world);
+        let mut candidates = vec![];
+
+        for (dx, dz) in HORIZONTAL {
+            let dir = glam::IVec3::new(dx, 0, dz);
+            let next = node + dir;
+            let diagonal = dx != 0 && dz != 0;
+            // no cutting corners.
+            if diagonal
+                && !(self.fits(node + glam::IVec3::new(dx, 0, 0), world)
+                    && self.fits(node + glam::IVec3::new(0, 0, dz), world))
+            {
+                continue;
+            }
+
+            if (in_water || self.is_water(next, world)) && self.fits(next, world) {
+                candidates.push((next, MoveKind::Swim));
+            } else if self.is_standable(next, world) {
+                candidates.push((next, MoveKind::Walk));
+            }
+            if diagonal {
+                continue;
+            }
+
+            if head_room && self.is_standable(next + up, world) {
+                candidates.push((next + up, MoveKind::Ascend));
+            }
+            if !self.fits(next, world) || self.is_standable(next, world) {
+                continue;
+            }
+
+            for drop in 1..=3 {
+                let to = next - up * drop;
+                if !self.fits(to, world) {
+                    break;
+                }
+                if self.is_standable(to, world) {
+                    candidates.push((to, MoveKind::Descend));
+                    break;
+                }
+            }
+
+            if !head_room || in_water {
+                continue;
+            }
+            for distance in 2..=4 {
+                let over = node + dir * (distance - 1);
+                if !self.fits(over, world) || !self.fits(over + up, world) {
+                    break;
+                }
+                for dy in [0, -1] {
+                    let to = node + dir * distance + up * dy;
+                    if self.is_standable(to, world) {
+                        candidates.push((to, MoveKind::SprintJump));
+                        break;
+                    }
+                }
+            }
+        }
+
+        for dy in [1, -1] {
+            let to = node + up * dy;
+            if !self.fits(to, world) {
+                continue;
+            }
+            let kind = if self.is_climbable(node, world) || self.is_climbable(to, world) {
+                MoveKind::Climb
+            } else if in_water || self.is_water(to, world) {
+                MoveKind::Swim
+            } else {
+                continue;
+            };
+            // climbing down a ladder is only worth it onto something.
+            if dy > 0 || self.is_standable(to, world) {
+                candidates.push((to, kind));
+            }
+        }
+        candidates
+    }
+
+    /// the quickest attempt at the move, none if none of them make it.
+    fn measure(
+        &self,
+        from: glam::IVec3,
+        to: glam::IVec3,
+        kind: MoveKind,
+        world: &impl World,
+    ) -> Option<Segment> {
+        let offset = to - from;
+        let rotation = if offset.x == 0 && offset.z == 0 {
+            Rotation::default()
+        } else {
+            Rotation::from_direction(glam::Vec3A::new(offset.x as f32, 0.0, offset.z as f32))
+        };
+
+        let attempts = match kind {
+            MoveKind::Walk => vec![Attempt::forward(true), Attempt::forward(false)],
+            MoveKind::Ascend => (0..3)
+                .flat_map(|tick| {
+                    [
+                        Attempt::forward(false).jump(tick),
+                        Attempt::forward(true).jump(tick),
+                    ]
+                })
+                .collect(),
+            MoveKind::Descend => vec![
+                Attempt::forward(false),
+                Attempt::forward(false).brake(),
+                Attempt::forward(true).brake(),
+            ],
+            // gaps get the whole search: run-ups, strafes, walk jumps.
+            MoveKind::SprintJump => {
+                let plan =
+                    self.reach
+                        .find(from.as_vec3a(), to.as_vec3a(), Approach::Standing, world)?;
+                return Some(Segment {
+                    from,
+                    to,
+                    kind,
+                    inputs: plan.inputs,
+                });
+            }
+            MoveKind::Swim | MoveKind::Climb if offset.y > 0 => {
+                vec![Attempt::default().jump(0), Attempt::forward(false).jump(0)]
+            }
+            MoveKind::Swim | MoveKind::Climb if offset.y < 0 => vec![
+                Attempt::default(),
+                Attempt {
+                    sneak: true,
+                    ..Default::default()
+                },
+            ],
+            MoveKind::Swim | MoveKind::Climb => vec![
+                Attempt::forward(false).jump(0),
+                Attempt::forward(true).jump(0),
+                Attempt::forward(false),
+            ],
+        };
+
+        let mut best: Option<Vec<TickInput>> = None;
+        for attempt in attempts {
+            let limit = best.as_ref().map_or(usize::MAX, |inputs| inputs.len());
+            if let Some(inputs) = self.run(from, to, attempt, rotation, limit, world) {
+                best = Some(inputs);
+            }
+        }
+        best.map(|inputs| Segment {
+            from,
+            to,
+            kind,
+            inputs,
+        })
+    }
+
+    /// simulates one attempt, giving up once it's no shorter than `limit`.
+    fn run(
+        &self,
+        from: glam::IVec3,
+        to: glam::IVec3,
+        attempt: Attempt,
+        rotation: Rotation,
+        limit: usize,
+        world: &impl World,
+    ) -> Option<Vec<TickInput>> {
+        let supported = self.is_supported(from, world);
+        let mut ctx = start_player(from.as_vec3a() + glam::Vec3A::new(0.5, 0.0, 0.5), supported);
+        let goal = if self.is_water(to, world) || self.is_climbable(to, world) {
+            Goal::Enter(to.as_vec3a())
+        } else {
+            Goal::Stand(to.as_vec3a())
+        };
+        rollout(
+            self.get_simulator(),
+            &mut ctx,
+            goal,
+            self.max_move_ticks,
+            limit,
+            world,
+            |tick, airborne, landed| {
+                let braking = attempt.brake && airborne;
+                TickInput::new(
+                    ControlStateHandler {
+                        forward: attempt.forward && !braking,
+                        back: braking,
+                        // one jump per move, landing short doesn't get another.
+                        jump: attempt.jump_from.is_some_and(|j| tick >= j) && !landed,
+                        sprint: attempt.sprint && !braking,
+                        sneak: attempt.sneak,
+                        ..Default::default()
+                    },
+                    rotation,
+                )
+            },
+        )
+    }
+
+    /// the player's box with its feet at the bottom of `pos`, centered.
+    fn player_bb(&self, pos: glam::IVec3) -> AABB {
+        let center = pos.as_vec3() + glam::Vec3::new(0.5, 0.0, 0.5);
+        AABB::new(
+            center.x - self.half_width,
+            center.y,
+            center.z - self.half_width,
+            center.x + self.half_width,
+            center.y + self.height,
+            center.z + self.half_width,
+        )
+    }
+
+    /// a player fits with its feet in `pos`.
+    fn fits(&self, pos: glam::IVec3, world: &impl World) -> bool {
+        collision::no_collision(&self.player_bb(pos), world)
+    }
+
+    /// something solid right under the feet.
+    fn is_supported(&self, pos: glam::IVec3, world: &impl World) -> bool {
+        let bb = self.player_bb(pos);
+        let below = AABB::new(
+            bb.min_x,
+            bb.min_y - 0.05,
+            bb.min_z,
+            bb.max_x,
+            bb.min_y,
+            bb.max_z,
+        );
+        !collision::no_collision(&below, world)
+    }
+
+    /// somewhere a player can stay: fits and is held up by a block, water or a ladder.
+    fn is_standable(&self, pos: glam::IVec3, world: &impl World) -> bool {
+        self.fits(pos, world)
+            && (self.is_supported(pos, world)
+                || self.is_water(pos, world)
+                || self.is_climbable(pos, world))
+    }
+
+    fn is_water(&self, pos: glam::IVec3, world: &impl World) -> bool {
+        self.has_type(&self.blocks.water, pos, world)
+    }
+
+    fn is_climbable(&self, pos: glam::IVec3, world: &impl World) -> bool {
+        self.has_type(&self.blocks.climbable, pos, world)
+    }
+
+    fn has_type(&self, ids: &HashSet<u32>, pos: glam::IVec3, world: &impl World) -> bool {
+        world
+            .get_block(&pos.as_vec3a())
+            .is_some_and(|b| ids.contains(&b.b_type))
+    }
+}
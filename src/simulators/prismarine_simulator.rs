@@ -1,12 +1,18 @@
 use std::collections::{HashMap, HashSet};
 
 use crate::{
+    blocks::shape_registry::ShapeRegistry,
     calc::aabb::AABB,
     settings::{physics_settings, PlayerAttribute, PlayerAttributeModifier},
     states::physics_context::EntityPhysicsContext,
 };
 
-use super::{Block, World};
+use super::{
+    collision,
+    events::{self, PhysicsEvent},
+    trace::{BlockEffect, TraceStage},
+    Block, Simulator, World,
+};
 
 #[derive(Default)]
 pub struct PrismarineSimulator {
@@ -35,6 +41,43 @@ fn glam_translate(mut org: glam::Vec3A, x: f32, y: f32, z: f32) -> glam::Vec3A {
 }
 
 impl PrismarineSimulator {
+    /// looks up every block the simulator special-cases by name.
+    /// blocks missing from the registry never match.
+    pub fn from_registry(registry: &ShapeRegistry) -> Self {
+        let id = |name: &str| registry.get_block_id(name).unwrap_or(u32::MAX);
+
+        let mut block_slipperiness = HashMap::new();
+        for (name, slipperiness) in [
+            ("slime_block", 0.8),
+            ("ice", 0.98),
+            ("packed_ice", 0.98),
+            ("frosted_ice", 0.98),
+            ("blue_ice", 0.989),
+        ] {
+            if let Some(b_type) = registry.get_block_id(name) {
+                block_slipperiness.insert(b_type, slipperiness);
+            }
+        }
+
+        Self {
+            slime_block_id: id("slime_block"),
+            soulsand_id: id("soul_sand"),
+            web_id: id("cobweb"),
+            honeyblock_id: id("honey_block"),
+            bubblecolumn_id: id("bubble_column"),
+            ladder_id: id("ladder"),
+            vine_id: id("vine"),
+            water_id: id("water"),
+            lava_id: id("lava"),
+            water_like: ["seagrass", "tall_seagrass", "kelp", "kelp_plant", "bubble_column"]
+                .into_iter()
+                .filter_map(|name| registry.get_block_id(name))
+                .collect(),
+            movement_speed_attribute: "generic.movement_speed".to_string(),
+            block_slipperiness,
+        }
+    }
+
     fn support_feature(_key: &str) -> bool {
         return false;
     }
@@ -66,7 +109,7 @@ impl PrismarineSimulator {
             cursor.x = q_bb_fl.min_x;
             while cursor.x <= q_bb_fl.max_x {
                 if let Some(block) = world.get_block(&cursor) {
-                    let b_pos = block.position;
+                    let b_pos = block.get_shape_origin();
                     for shape in block.shapes {
                         let bb =
                             AABB::new(shape[0], shape[1], shape[2], shape[3], shape[4], shape[5])
@@ -83,31 +126,12 @@ impl PrismarineSimulator {
     }
 
     pub fn get_surrounding_block_bbs(query_bb: &AABB, world: &impl World) -> Vec<AABB> {
-        let mut surrounding_bbs = vec![];
-        let q_bb_fl = query_bb.floored();
-        let mut cursor = glam::Vec3A::new(q_bb_fl.min_x, q_bb_fl.min_y - 1.0, q_bb_fl.min_z);
-        while cursor.y <= q_bb_fl.max_y {
-            cursor.z = q_bb_fl.min_z;
-            while cursor.z <= q_bb_fl.max_z {
-                cursor.x = q_bb_fl.min_x;
-                while cursor.x <= q_bb_fl.max_x {
-                    if let Some(block) = world.get_block(&cursor) {
-                        let b_pos = block.position;
-                        for shape in block.shapes {
-                            let bb = AABB::new(
-                                shape[0], shape[1], shape[2], shape[3], shape[4], shape[5],
-                            )
-                            .offset(b_pos.x, b_pos.y, b_pos.z);
-                            surrounding_bbs.push(bb);
-                        }
-                    }
-                    cursor.x += 1.0;
-                }
-                cursor.z += 1.0;
-            }
-            cursor.y += 1.0;
-        }
-        return surrounding_bbs;
+        collision::get_surrounding_block_bbs(query_bb, world)
+    }
+
+    /// block shapes plus the world border, what entities actually collide with when moving.
+    pub fn get_collision_bbs(entity_bb: &AABB, query_bb: &AABB, world: &impl World) -> Vec<AABB> {
+        collision::get_collision_bbs(entity_bb, query_bb, world)
     }
 
     #[allow(dead_code)]
@@ -145,6 +169,7 @@ impl PrismarineSimulator {
             return;
         }
 
+        let was_in_web = entity.state.is_in_web;
         if entity.state.is_in_web && !entity.entity_type.name.contains("arrow") {
             dx *= 0.25;
             dy *= 0.05;
@@ -153,6 +178,10 @@ impl PrismarineSimulator {
             entity.state.velocity.y = 0.0;
             entity.state.velocity.z = 0.0;
             entity.state.is_in_web = false;
+            entity.record(TraceStage::BlockEffect {
+                effect: BlockEffect::Web,
+                velocity: glam::Vec3A::new(dx, dy, dz),
+            });
         }
 
         let old_old_vel_x = dx; // was const
@@ -232,7 +261,7 @@ impl PrismarineSimulator {
 
         let mut player_bb = entity.get_current_bb_with_pose();
         let query_bb = player_bb.extend(dx, dy, dz);
-        let surrounding_bbs = Self::get_surrounding_block_bbs(&query_bb, world);
+        let surrounding_bbs = Self::get_collision_bbs(&player_bb, &query_bb, world);
         let old_bb = player_bb.clone();
 
         for block_bb in &surrounding_bbs {
@@ -262,7 +291,7 @@ impl PrismarineSimulator {
 
             dy = entity.step_height;
             let query_bb = old_bb.extend(old_vel_x, dy, old_vel_z);
-            let surrounding_bbs = Self::get_surrounding_block_bbs(&query_bb, world);
+            let surrounding_bbs = Self::get_collision_bbs(&old_bb, &query_bb, world);
 
             let mut bb1 = old_bb.clone();
             let mut bb2 = old_bb.clone();
@@ -316,14 +345,28 @@ impl PrismarineSimulator {
 
             player_bb = player_bb.offset(0.0, dy, 0.0);
 
-            if old_vel_x_col * old_vel_x_col + old_vel_z_col * old_vel_z_col >= dx * dx + dz * dz {
+            let taken =
+                old_vel_x_col * old_vel_x_col + old_vel_z_col * old_vel_z_col < dx * dx + dz * dz;
+            entity.record(TraceStage::StepUp {
+                candidate: glam::Vec3A::new(dx, dy, dz),
+                taken,
+            });
+            if !taken {
                 dx = old_vel_x_col;
                 dy = old_vel_y_col;
                 dz = old_vel_z_col;
                 player_bb = old_bb_col;
+            } else {
+                let height = player_bb.min_y - old_bb.min_y;
+                entity.emit(PhysicsEvent::SteppedUp { height });
             }
         }
 
+        entity.record(TraceStage::Collision {
+            requested: glam::Vec3A::new(old_old_vel_x, old_vel_y, old_old_vel_z),
+            allowed: glam::Vec3A::new(dx, dy, dz),
+        });
+
         // Update flags
         // up until this point, pos == entity.state.position
         Self::set_pos_to_bb_center_bottom(entity, &player_bb);
@@ -331,10 +374,13 @@ impl PrismarineSimulator {
         // reassign to entity.state.position (we're deviating here).
         // this should still match though.
 
+        let was_on_ground = entity.state.on_ground;
+        let was_collided_horizontally = entity.state.is_collided_horizontally;
         entity.state.sneak_collision = dx != old_old_vel_x || dz != old_old_vel_z;
         entity.state.is_collided_horizontally = dx != old_vel_x || dz != old_vel_z;
         entity.state.is_collided_vertically = dy != old_vel_y;
         entity.state.on_ground = entity.state.is_collided_vertically && old_vel_y < 0.0;
+        events::track_move(entity, was_on_ground, was_collided_horizontally, dy);
 
         let block_at_feet = world.get_block(&glam::Vec3A::new(
             entity.state.position.x,
@@ -355,6 +401,12 @@ impl PrismarineSimulator {
                 && !entity.state.control_states.sneak
             {
                 entity.state.velocity.y = -entity.state.velocity.y;
+                let velocity = entity.state.velocity.y;
+                entity.record(TraceStage::BlockEffect {
+                    effect: BlockEffect::SlimeBounce,
+                    velocity: entity.state.velocity,
+                });
+                entity.emit(PhysicsEvent::SlimeBounce { velocity });
             } else {
                 entity.state.velocity.y = 0.0;
             }
@@ -377,9 +429,17 @@ impl PrismarineSimulator {
                             if block.b_type == self.soulsand_id {
                                 entity.state.velocity.x *= physics_settings::SOUL_SAND_SPEED;
                                 entity.state.velocity.z *= physics_settings::SOUL_SAND_SPEED;
+                                entity.record(TraceStage::BlockEffect {
+                                    effect: BlockEffect::SoulSand,
+                                    velocity: entity.state.velocity,
+                                });
                             } else if block.b_type == self.honeyblock_id {
                                 entity.state.velocity.x *= physics_settings::HONEY_BLOCK_SPEED;
                                 entity.state.velocity.z *= physics_settings::HONEY_BLOCK_SPEED;
+                                entity.record(TraceStage::BlockEffect {
+                                    effect: BlockEffect::HoneyBlock,
+                                    velocity: entity.state.velocity,
+                                });
                             }
                         }
                         if block.b_type == self.web_id {
@@ -387,7 +447,8 @@ impl PrismarineSimulator {
                         }
                         // no blockEffects check here, apparently all entities are affected by self.
                         else if block.b_type == self.bubblecolumn_id {
-                            let down = block.metadata == 0; // uhhh. actually, this could be !0 which is true.
+                            // drag=true is state 0, so old worlds reporting only metadata still work.
+                            let down = block.get_properties().drag.unwrap_or(block.metadata == 0);
                             let above_block = world.get_block(&glam::Vec3A::new(
                                 cursor.x,
                                 cursor.y + 1.0,
@@ -409,6 +470,10 @@ impl PrismarineSimulator {
                                     .max_up
                                     .max(entity.state.velocity.y + bubble_drag.up);
                             }
+                            entity.record(TraceStage::BlockEffect {
+                                effect: BlockEffect::BubbleColumn,
+                                velocity: entity.state.velocity,
+                            });
                         }
                     }
 
@@ -425,12 +490,25 @@ impl PrismarineSimulator {
                 if block_below.b_type == self.soulsand_id {
                     entity.state.velocity.x *= physics_settings::SOUL_SAND_SPEED;
                     entity.state.velocity.z *= physics_settings::SOUL_SAND_SPEED;
+                    entity.record(TraceStage::BlockEffect {
+                        effect: BlockEffect::SoulSand,
+                        velocity: entity.state.velocity,
+                    });
                 } else if block_below.b_type == self.honeyblock_id {
                     entity.state.velocity.x *= physics_settings::HONEY_BLOCK_SPEED;
                     entity.state.velocity.z *= physics_settings::HONEY_BLOCK_SPEED;
+                    entity.record(TraceStage::BlockEffect {
+                        effect: BlockEffect::HoneyBlock,
+                        velocity: entity.state.velocity,
+                    });
                 }
             }
         }
+
+        if entity.state.is_in_web && !was_in_web {
+            entity.state.fall_distance = 0.0;
+            entity.emit(PhysicsEvent::EnteredWeb);
+        }
     }
 
     fn apply_heading(
@@ -452,16 +530,12 @@ impl PrismarineSimulator {
         strafe *= speed;
         forward *= speed;
 
-        let yaw = std::f32::consts::PI - entity.state.yaw;
+        let yaw = entity.state.rotation.get_vanilla_yaw();
         let (sin, cos) = yaw.sin_cos();
 
-        if sin >= f32::EPSILON {
-            entity.state.velocity.x += strafe * cos - forward * sin;
-        }
-
-        if cos >= f32::EPSILON {
-            entity.state.velocity.z += forward * cos + strafe * sin;
-        }
+        // both components apply whatever the sign, otherwise only one quadrant moves.
+        entity.state.velocity.x += strafe * cos - forward * sin;
+        entity.state.velocity.z += forward * cos + strafe * sin;
     }
 
     fn is_on_ladder(
@@ -568,8 +642,15 @@ impl PrismarineSimulator {
         if block.b_type != self.water_id {
             return -1.0;
         }
-        let meta = block.metadata;
-        return if meta >= 8 { 0.0 } else { meta as f32 };
+        let level = block
+            .get_properties()
+            .level
+            .map_or(block.metadata, |level| level as u32);
+        if level >= 8 {
+            0.0
+        } else {
+            level as f32
+        }
     }
 
     fn get_flow(&self, block: &Block, world: &impl World /*prismarine-world*/) -> glam::Vec3A {
@@ -603,7 +684,11 @@ impl PrismarineSimulator {
             }
         }
 
-        if block.metadata >= 8 {
+        let level = block
+            .get_properties()
+            .level
+            .map_or(block.metadata, |level| level as u32);
+        if level >= 8 {
             for [dx, dz] in [[0.0, 1.0], [-1.0, 0.0], [0.0, -1.0], [1.0, 0.0]] {
                 {
                     let adj_block = world.get_block(&glam_offset(&block.position, dx, 0.0, dz));
@@ -736,7 +821,14 @@ impl PrismarineSimulator {
                 }
             }
 
+            entity.record(TraceStage::Friction {
+                inertia,
+                acceleration,
+            });
             Self::apply_heading(entity, strafe, forward, acceleration);
+            entity.record(TraceStage::Heading {
+                velocity: entity.state.velocity,
+            });
 
             if entity.collision_behavior.block_effects
                 && self.is_on_ladder(&entity.state.position, world)
@@ -774,6 +866,7 @@ impl PrismarineSimulator {
                         && entity.state.control_states.jump))
             {
                 entity.state.velocity.y = physics_settings::LADDER_CLIMB_SPEED; // climb ladder
+                entity.emit(PhysicsEvent::ClimbedLadder);
             }
 
             // Not adding an additional function call. No point.
@@ -799,6 +892,9 @@ impl PrismarineSimulator {
 
             entity.state.velocity.x *= inertia;
             entity.state.velocity.z *= inertia;
+            entity.record(TraceStage::Drag {
+                velocity: entity.state.velocity,
+            });
         } else {
             // Water / Lava movement
             let last_y = pos.y;
@@ -826,7 +922,14 @@ impl PrismarineSimulator {
                 }
             }
 
+            entity.record(TraceStage::Friction {
+                inertia: horizontal_inertia,
+                acceleration,
+            });
             Self::apply_heading(entity, strafe, forward, acceleration);
+            entity.record(TraceStage::Heading {
+                velocity: entity.state.velocity,
+            });
             self.move_entity(
                 entity,
                 entity.state.velocity.x,
@@ -851,6 +954,9 @@ impl PrismarineSimulator {
             }
             entity.state.velocity.x *= horizontal_inertia;
             entity.state.velocity.z *= horizontal_inertia;
+            entity.record(TraceStage::Drag {
+                velocity: entity.state.velocity,
+            });
 
             if entity.state.is_collided_horizontally
                 && self.does_not_collide(
@@ -869,16 +975,16 @@ impl PrismarineSimulator {
         }
     }
 
-    pub fn simulate(
+    fn tick_entity(
         &self,
-        mut entity: EntityPhysicsContext,
+        entity: &mut EntityPhysicsContext,
         world: &impl World, /*prismarine-world*/
-    ) -> EntityPhysicsContext {
-        if !Self::should_move_entity(&entity) {
+    ) {
+        if !Self::should_move_entity(entity) {
             entity.state.velocity.x = 0.0;
             entity.state.velocity.y = 0.0;
             entity.state.velocity.z = 0.0;
-            return entity;
+            return;
         }
 
         let mut vel = entity.state.velocity;
@@ -891,8 +997,15 @@ impl PrismarineSimulator {
 
         // assume that if we shouldn't move entity, isInWater and isInLava are already properly set.
 
+        let (was_in_water, was_in_lava) = (entity.state.is_in_water, entity.state.is_in_lava);
         entity.state.is_in_water = self.is_in_water_apply_current(&water_bb, &mut vel, world);
         entity.state.is_in_lava = Self::is_material_in_bb(&lava_bb, self.lava_id, world);
+        events::track_fluids(entity, was_in_water, was_in_lava);
+        entity.record(TraceStage::Fluids {
+            in_water: entity.state.is_in_water,
+            in_lava: entity.state.is_in_lava,
+            velocity: entity.state.velocity,
+        });
 
         // Reset velocity component if it falls under the threshold
         if entity.state.velocity.x.abs() < physics_settings::NEGLIGEABLE_VELOCITY {
@@ -932,25 +1045,23 @@ impl PrismarineSimulator {
                         entity.state.velocity.y += 0.1 * entity.state.jump_boost as f32;
                     }
                     if entity.state.control_states.sprint {
-                        let yaw = std::f32::consts::PI - entity.state.yaw;
-
-                        let (vx, vz) = yaw.sin_cos();
-                        if vx >= f32::EPSILON {
-                            entity.state.velocity.x -= yaw.sin() * 0.2;
-                        }
-                        if vz >= f32::EPSILON {
-                            entity.state.velocity.z += yaw.cos() * 0.2;
-                        }
+                        let yaw = entity.state.rotation.get_vanilla_yaw();
+                        entity.state.velocity.x -= yaw.sin() * 0.2;
+                        entity.state.velocity.z += yaw.cos() * 0.2;
                     }
                     entity.state.jump_ticks = physics_settings::AUTO_JUMP_COOLDOWN;
                 }
+                entity.record(TraceStage::Jump {
+                    velocity: entity.state.velocity,
+                });
             } else {
                 entity.state.jump_ticks = 0; // reset autojump cooldown
             }
             entity.state.jump_queued = false;
 
-            let mut strafe = ((entity.state.control_states.right as u8 as f32)
-                - (entity.state.control_states.left as u8 as f32))
+            // original: xxa = left - right, positive strafe is to the left.
+            let mut strafe = ((entity.state.control_states.left as u8 as f32)
+                - (entity.state.control_states.right as u8 as f32))
                 * 0.98;
             let mut forward = ((entity.state.control_states.forward as u8 as f32)
                 - (entity.state.control_states.back as u8 as f32))
@@ -971,11 +1082,24 @@ impl PrismarineSimulator {
             }
 
             // entity.state.velocity = vel;
-            self.move_entity_with_heading(&mut entity, strafe, forward, world);
+            entity.record(TraceStage::Input { strafe, forward });
+            self.move_entity_with_heading(entity, strafe, forward, world);
         } else {
-            self.move_entity_with_heading(&mut entity, 0.0, 0.0, world);
+            entity.record(TraceStage::Input {
+                strafe: 0.0,
+                forward: 0.0,
+            });
+            self.move_entity_with_heading(entity, 0.0, 0.0, world);
         }
 
-        return entity;
+        entity.state.is_below_world = world.get_min_y().is_some_and(|min_y| {
+            entity.state.position.y < min_y - physics_settings::BELOW_WORLD_DEPTH
+        });
+    }
+}
+
+impl Simulator for PrismarineSimulator {
+    fn tick(&self, ctx: &mut EntityPhysicsContext, world: &impl World) {
+        self.tick_entity(ctx, world);
     }
 }
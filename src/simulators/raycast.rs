@@ -0,0 +1,175 @@
+use crate::{blocks::properties::Direction, calc::aabb::AABB};
+
+use super::{Block, World};
+
+/// What a ray ran into first.
+#[derive(Clone)]
+pub struct RaycastHit {
+    pub block: Block,
+    /// the side of the shape that was hit.
+    pub face: Direction,
+    /// exact point on that face.
+    pub position: glam::Vec3A,
+    /// from the origin, in blocks.
+    pub distance: f32,
+    /// the origin is inside the shape. distance is 0, the face is the one the ray looks at.
+    pub inside: bool,
+}
+
+/// first block shape along `direction` from `origin`, within `max_distance` blocks.
+/// shapes are the ones the world reports, see `raycast_shapes` for outlines.
+/// none if `max_distance` isn't finite.
+pub fn raycast(
+    world: &impl World,
+    origin: glam::Vec3A,
+    direction: glam::Vec3A,
+    max_distance: f32,
+) -> Option<RaycastHit> {
+    raycast_shapes(world, origin, direction, max_distance, |block| {
+        block.get_shapes().to_vec()
+    })
+}
+
+/// same as `raycast`, testing the boxes `shapes` returns for each block instead,
+/// e.g. outline shapes for picking blocks that have no collision.
+/// original: BlockGetter.clip, walking the blocks the ray crosses one by one.
+pub fn raycast_shapes(
+    world: &impl World,
+    origin: glam::Vec3A,
+    direction: glam::Vec3A,
+    max_distance: f32,
+    shapes: impl Fn(&Block) -> Vec<[f32; 6]>,
+) -> Option<RaycastHit> {
+    let direction = direction.normalize_or_zero();
+    if direction == glam::Vec3A::ZERO || !max_distance.is_finite() {
+        return None;
+    }
+
+    // Amanatides & Woo: t is the distance along the ray to the next cell boundary per axis.
+    let mut cell = origin.floor();
+    let step = direction.signum();
+    let next_boundary = cell + step.max(glam::Vec3A::ZERO);
+    let mut t_max = glam::Vec3A::select(
+        direction.cmpne(glam::Vec3A::ZERO),
+        (next_boundary - origin) / direction,
+        glam::Vec3A::splat(f32::INFINITY),
+    );
+    let t_delta = glam::Vec3A::select(
+        direction.cmpne(glam::Vec3A::ZERO),
+        (1.0 / direction).abs(),
+        glam::Vec3A::splat(f32::INFINITY),
+    );
+
+    let mut t = 0.0;
+    while t <= max_distance {
+        if let Some(block) = world.get_block(&cell) {
+            let b_pos = block.get_shape_origin();
+            let mut closest: Option<(f32, Direction, bool)> = None;
+            for shape in shapes(&block) {
+                let bb = AABB::new(shape[0], shape[1], shape[2], shape[3], shape[4], shape[5])
+                    .offset(b_pos.x, b_pos.y, b_pos.z);
+                if let Some((hit, face, inside)) = clip(&bb, origin, direction) {
+                    let closer = match closest {
+                        Some((best, _, _)) => hit < best,
+                        None => true,
+                    };
+                    if closer {
+                        closest = Some((hit, face, inside));
+                    }
+                }
+            }
+            if let Some((distance, face, inside)) = closest {
+                if distance > max_distance {
+                    return None;
+                }
+                return Some(RaycastHit {
+                    block,
+                    face,
+                    position: origin + direction * distance,
+                    distance,
+                    inside,
+                });
+            }
+        }
+
+        // step into whichever neighbour the ray reaches first.
+        if t_max.x <= t_max.y && t_max.x <= t_max.z {
+            t = t_max.x;
+            t_max.x += t_delta.x;
+            cell.x += step.x;
+        } else if t_max.y <= t_max.z {
+            t = t_max.y;
+            t_max.y += t_delta.y;
+            cell.y += step.y;
+        } else {
+            t = t_max.z;
+            t_max.z += t_delta.z;
+            cell.z += step.z;
+        }
+    }
+    None
+}
+
+/// distance to where the ray enters `bb`, through which face, and whether it starts inside.
+/// none if it misses.
+/// original: AABB.clip, plus VoxelShape.clip's inside check.
+fn clip(bb: &AABB, origin: glam::Vec3A, direction: glam::Vec3A) -> Option<(f32, Direction, bool)> {
+    let axes = [
+        (bb.min_x, bb.max_x, Direction::West, Direction::East),
+        (bb.min_y, bb.max_y, Direction::Down, Direction::Up),
+        (bb.min_z, bb.max_z, Direction::North, Direction::South),
+    ];
+    let mut near = f32::NEG_INFINITY;
+    let mut far = f32::INFINITY;
+    let mut face = None;
+    for (axis, (min, max, min_face, max_face)) in axes.into_iter().enumerate() {
+        let (o, d) = (origin[axis], direction[axis]);
+        if d == 0.0 {
+            if o <= min || o >= max {
+                return None;
+            }
+            continue;
+        }
+        let (t_min, t_max) = ((min - o) / d, (max - o) / d);
+        let (enter, exit, entered) = if d > 0.0 {
+            (t_min, t_max, min_face)
+        } else {
+            (t_max, t_min, max_face)
+        };
+        if enter > near {
+            near = enter;
+            face = Some(entered);
+        }
+        far = far.min(exit);
+    }
+    if near > far || far < 0.0 {
+        return None;
+    }
+    if near < 0.0 {
+        return Some((0.0, get_facing_face(direction), true));
+    }
+    face.map(|face| (near, face, false))
+}
+
+/// the face a ray looking along `direction` sees, on its main axis.
+/// original: Direction.getNearest(direction).getOpposite()
+fn get_facing_face(direction: glam::Vec3A) -> Direction {
+    let abs = direction.abs();
+    if abs.x >= abs.y && abs.x >= abs.z {
+        if direction.x > 0.0 {
+            Direction::West
+        } else {
+            Direction::East
+        }
+    } else if abs.y >= abs.z {
+        if direction.y > 0.0 {
+            Direction::Down
+        } else {
+            Direction::Up
+        }
+    } else if direction.z > 0.0 {
+        Direction::North
+    } else {
+        Direction::South
+    }
+}
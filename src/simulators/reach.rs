@@ -0,0 +1,211 @@
+use std::f32::consts::FRAC_PI_4;
+
+use crate::{
+    calc::angle::Rotation,
+    settings::physics_settings,
+    states::player_context::{ControlStateHandler, TickInput},
+};
+
+use super::{
+    prismarine_simulator::PrismarineSimulator,
+    rollout::{rollout, start_player, Goal},
+    Simulator, World,
+};
+
+/// longest a single jump (or drop) is followed before giving up.
+pub const DEFAULT_MAX_AIR_TICKS: u32 = 40;
+/// most ticks spent backing up and running up on the start block.
+pub const DEFAULT_MAX_RUN_UP: u32 = 4;
+
+/// How the player arrives at the start block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Approach {
+    Standing,
+    /// already sprinting towards the target at full ground speed.
+    Sprinting,
+}
+
+/// A working input sequence, one `TickInput` per tick.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JumpPlan {
+    pub inputs: Vec<TickInput>,
+    /// where the player ends up standing.
+    pub landing: glam::Vec3A,
+}
+
+impl JumpPlan {
+    pub fn get_ticks(&self) -> usize {
+        self.inputs.len()
+    }
+}
+
+/// one way of attempting the move.
+#[derive(Clone, Copy)]
+struct Attempt {
+    sprint: bool,
+    jump: bool,
+    /// yaw offset from facing the target, and the strafe key held with it.
+    turn: f32,
+    left: bool,
+    right: bool,
+    back_up: u32,
+    run_up: u32,
+}
+
+/// "From this block, can I reach that block?"
+///
+/// Blocks are the ones the player's feet are in. Tries sprint and walk jumps, drops,
+/// 45° strafes and short back-up/run-up momentum on the start block, and returns the
+/// shortest sequence that ends standing in the target block.
+pub struct ReachabilitySearch<S: Simulator = PrismarineSimulator> {
+    simulator: S,
+    max_air_ticks: u32,
+    max_run_up: u32,
+}
+
+impl Default for ReachabilitySearch {
+    fn default() -> Self {
+        Self::new(PrismarineSimulator::default())
+    }
+}
+
+impl<S: Simulator> ReachabilitySearch<S> {
+    pub fn new(simulator: S) -> Self {
+        Self {
+            simulator,
+            max_air_ticks: DEFAULT_MAX_AIR_TICKS,
+            max_run_up: DEFAULT_MAX_RUN_UP,
+        }
+    }
+
+    pub fn with_max_air_ticks(mut self, max_air_ticks: u32) -> Self {
+        self.max_air_ticks = max_air_ticks;
+        self
+    }
+
+    pub fn with_max_run_up(mut self, max_run_up: u32) -> Self {
+        self.max_run_up = max_run_up;
+        self
+    }
+
+    pub fn get_simulator(&self) -> &S {
+        &self.simulator
+    }
+
+    /// shortest plan from standing centered in `start` to standing in `target`,
+    /// none if nothing in the search space gets there.
+    pub fn find(
+        &self,
+        start: glam::Vec3A,
+        target: glam::Vec3A,
+        approach: Approach,
+        world: &impl World,
+    ) -> Option<JumpPlan> {
+        let (start, target) = (start.floor(), target.floor());
+        if start == target {
+            return Some(JumpPlan {
+                inputs: vec![],
+                landing: start + glam::Vec3A::new(0.5, 0.0, 0.5),
+            });
+        }
+
+        let mut best: Option<JumpPlan> = None;
+        for attempt in self.attempts() {
+            let limit = best.as_ref().map_or(usize::MAX, |plan| plan.get_ticks());
+            if let Some(plan) = self.try_attempt(attempt, start, target, approach, limit, world) {
+                best = Some(plan);
+            }
+        }
+        best
+    }
+
+    fn attempts(&self) -> Vec<Attempt> {
+        let mut attempts = vec![];
+        for sprint in [true, false] {
+            for jump in [true, false] {
+                // a positive turn looks left of the target, so strafing right brings the
+                // movement back onto it (and the other way round).
+                for (turn, left, right) in [
+                    (0.0, false, false),
+                    (FRAC_PI_4, false, true),
+                    (-FRAC_PI_4, true, false),
+                ] {
+                    for back_up in 0..=self.max_run_up {
+                        for run_up in 0..=(self.max_run_up - back_up) {
+                            attempts.push(Attempt {
+                                sprint,
+                                jump,
+                                turn,
+                                left,
+                                right,
+                                back_up,
+                                run_up,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        attempts
+    }
+
+    /// runs one attempt, giving up once it's no shorter than `limit`.
+    fn try_attempt(
+        &self,
+        attempt: Attempt,
+        start: glam::Vec3A,
+        target: glam::Vec3A,
+        approach: Approach,
+        limit: usize,
+        world: &impl World,
+    ) -> Option<JumpPlan> {
+        let facing = Rotation::from_direction(target - start);
+        let rotation = Rotation::new(facing.yaw + attempt.turn, 0.0);
+
+        let mut ctx = start_player(start + glam::Vec3A::new(0.5, 0.0, 0.5), true);
+        if approach == Approach::Sprinting {
+            ctx.state.velocity += sprint_velocity(facing);
+        }
+
+        let total = attempt.back_up + attempt.run_up + self.max_air_ticks;
+        let inputs = rollout(
+            &self.simulator,
+            &mut ctx,
+            Goal::Land(target),
+            total,
+            limit,
+            world,
+            |tick, _, _| {
+                let backing = tick < attempt.back_up;
+                let jumping = tick >= attempt.back_up + attempt.run_up;
+                TickInput::new(
+                    ControlStateHandler {
+                        forward: !backing,
+                        back: backing,
+                        left: attempt.left && !backing,
+                        right: attempt.right && !backing,
+                        jump: attempt.jump && jumping,
+                        sprint: attempt.sprint && !backing,
+                        sneak: false,
+                    },
+                    rotation,
+                )
+            },
+        )?;
+        Some(JumpPlan {
+            inputs,
+            landing: ctx.state.position,
+        })
+    }
+}
+
+/// stored velocity of a player sprinting on default-slipperiness ground for long enough,
+/// horizontally along `rotation`.
+/// original: the fixed point of v = (v + 0.98 * acceleration) * inertia.
+fn sprint_velocity(rotation: Rotation) -> glam::Vec3A {
+    let inertia = physics_settings::DEFAULT_SLIPPERINESS * physics_settings::AIRBORNE_INERTIA;
+    let speed = physics_settings::PLAYER_SPEED * (1.0 + physics_settings::SPRINT_SPEED);
+    let acceleration = speed * (0.1627714 / (inertia * inertia * inertia));
+    let v = 0.98 * acceleration * inertia / (1.0 - inertia);
+    Rotation::new(rotation.yaw, 0.0).get_direction() * v
+}
@@ -0,0 +1,160 @@
+use std::{collections::VecDeque, fmt};
+
+use crate::states::{
+    physics_context::EntityPhysicsContext, player_context::TickInput, EntityState,
+};
+
+use super::{Simulator, World};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReconcileError {
+    /// the tick already fell out of the history.
+    TooOld { tick: u64, oldest: u64 },
+    /// the tick hasn't been predicted yet.
+    NotPredicted(u64),
+}
+
+impl fmt::Display for ReconcileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooOld { tick, oldest } => {
+                write!(
+                    f,
+                    "tick {} is older than the oldest kept tick {}",
+                    tick, oldest
+                )
+            }
+            Self::NotPredicted(tick) => write!(f, "tick {} hasn't been predicted", tick),
+        }
+    }
+}
+
+impl std::error::Error for ReconcileError {}
+
+/// one predicted tick: the input it ran with and the state it ended in.
+#[derive(Clone)]
+pub struct PredictedTick {
+    pub tick: u64,
+    pub input: TickInput,
+    pub state: EntityState,
+}
+
+/// Client-side prediction with rollback.
+///
+/// Tick the local entity through `predict`, which keeps the last `capacity` inputs and
+/// resulting states. When the server corrects (or teleports) the entity as of some tick,
+/// `reconcile` puts the authoritative state there and replays every input after it.
+pub struct Reconciler {
+    capacity: usize,
+    history: VecDeque<PredictedTick>,
+    next_tick: u64,
+}
+
+impl Reconciler {
+    /// `capacity` should cover the round trip, e.g. 40 ticks for a 2 second worst case.
+    pub fn new(capacity: usize) -> Self {
+        Self::starting_at(capacity, 0)
+    }
+
+    /// for numbering ticks the way the server does, e.g. by its teleport/ack ids.
+    pub fn starting_at(capacity: usize, next_tick: u64) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            history: VecDeque::with_capacity(capacity.max(1)),
+            next_tick,
+        }
+    }
+
+    /// runs one tick with `input` and remembers it. returns the tick's number.
+    pub fn predict(
+        &mut self,
+        simulator: &impl Simulator,
+        ctx: &mut EntityPhysicsContext,
+        input: TickInput,
+        world: &impl World,
+    ) -> u64 {
+        input.apply(ctx);
+        simulator.tick(ctx, world);
+
+        let tick = self.next_tick;
+        if self.history.len() == self.capacity {
+            self.history.pop_front();
+        }
+        self.history.push_back(PredictedTick {
+            tick,
+            input,
+            state: ctx.state.clone(),
+        });
+        self.next_tick += 1;
+        tick
+    }
+
+    /// the server says the entity ended `tick` in `authoritative`.
+    /// rewinds to it, replays the inputs predicted since and leaves `ctx` in the corrected
+    /// current state, which is also returned. ticks before `tick` are forgotten.
+    pub fn reconcile(
+        &mut self,
+        simulator: &impl Simulator,
+        ctx: &mut EntityPhysicsContext,
+        tick: u64,
+        authoritative: EntityState,
+        world: &impl World,
+    ) -> Result<EntityState, ReconcileError> {
+        let index = self.index_of(tick)?;
+        self.history.drain(..index);
+
+        ctx.state = authoritative.clone();
+        self.history[0].state = authoritative;
+        for predicted in self.history.iter_mut().skip(1) {
+            predicted.input.apply(ctx);
+            simulator.tick(ctx, world);
+            predicted.state = ctx.state.clone();
+        }
+        Ok(ctx.state.clone())
+    }
+
+    fn index_of(&self, tick: u64) -> Result<usize, ReconcileError> {
+        let oldest = match self.history.front() {
+            Some(front) => front.tick,
+            None => return Err(ReconcileError::NotPredicted(tick)),
+        };
+        if tick >= self.next_tick {
+            Err(ReconcileError::NotPredicted(tick))
+        } else if tick < oldest {
+            Err(ReconcileError::TooOld { tick, oldest })
+        } else {
+            Ok((tick - oldest) as usize)
+        }
+    }
+
+    /// predicted (or already corrected) state at the end of `tick`, if still kept.
+    pub fn get_state(&self, tick: u64) -> Option<&EntityState> {
+        self.index_of(tick).ok().map(|i| &self.history[i].state)
+    }
+
+    pub fn get_history(&self) -> impl Iterator<Item = &PredictedTick> {
+        self.history.iter()
+    }
+
+    /// number the next `predict` call will return.
+    pub fn get_next_tick(&self) -> u64 {
+        self.next_tick
+    }
+
+    pub fn get_capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+
+    /// forgets everything, e.g. after a respawn or dimension change.
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+}
@@ -0,0 +1,94 @@
+use crate::states::{
+    context_builder::EntityPhysicsContextBuilder, physics_context::EntityPhysicsContext,
+    player_context::TickInput,
+};
+
+use super::{Simulator, World};
+
+/// Where a rollout has to get the player.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Goal {
+    /// touching down in this block, landing anywhere else fails.
+    Land(glam::Vec3A),
+    /// standing in this block. landing short on its level and walking on is fine.
+    Stand(glam::Vec3A),
+    /// in this block, standing or not (water, ladders). otherwise like `Stand`.
+    Enter(glam::Vec3A),
+    /// off the ground, anywhere.
+    LeaveGround,
+}
+
+/// a player at rest with its feet at `position`, what the planners start every attempt from.
+pub(crate) fn start_player(position: glam::Vec3A, on_ground: bool) -> EntityPhysicsContext {
+    let mut ctx = EntityPhysicsContextBuilder::player()
+        .position(position)
+        .on_ground(on_ground)
+        .build()
+        .expect("the player preset is valid");
+    if on_ground {
+        // what a player standing still has, otherwise the first tick doesn't count as grounded.
+        ctx.state.velocity.y = -0.0784;
+    }
+    ctx
+}
+
+/// Ticks `ctx` with `input(tick, airborne, landed)` until it gets to `goal`, returns the
+/// inputs it took. `airborne` is whether it has left the ground yet, `landed` whether it's
+/// back on it since.
+///
+/// Gives up after `max_ticks`, once it's no shorter than `limit`, when it lands where the
+/// goal doesn't allow, or when it drops below both the block it started in and the goal's.
+pub(crate) fn rollout(
+    simulator: &impl Simulator,
+    ctx: &mut EntityPhysicsContext,
+    goal: Goal,
+    max_ticks: u32,
+    limit: usize,
+    world: &impl World,
+    mut input: impl FnMut(u32, bool, bool) -> TickInput,
+) -> Option<Vec<TickInput>> {
+    let lowest = match goal {
+        Goal::Land(block) | Goal::Stand(block) | Goal::Enter(block) => {
+            block.y.min(ctx.state.position.y.floor())
+        }
+        Goal::LeaveGround => f32::MIN,
+    };
+    let mut inputs = vec![];
+    let mut airborne = false;
+    for tick in 0..max_ticks {
+        if inputs.len() + 1 >= limit {
+            return None;
+        }
+        let next = input(tick, airborne, airborne && ctx.state.on_ground);
+        next.apply(ctx);
+        simulator.tick(ctx, world);
+        inputs.push(next);
+
+        let state = &ctx.state;
+        let feet = state.position.floor();
+        let arrived = match goal {
+            Goal::Land(block) | Goal::Stand(block) => state.on_ground && feet == block,
+            Goal::Enter(block) => feet == block,
+            Goal::LeaveGround => !state.on_ground,
+        };
+        if arrived {
+            return Some(inputs);
+        }
+
+        if !state.on_ground {
+            airborne = true;
+        } else if airborne {
+            match goal {
+                // landed somewhere else.
+                Goal::Land(_) => return None,
+                // landed on the wrong level, walking on from a wrong block is fine.
+                Goal::Stand(block) | Goal::Enter(block) if feet.y != block.y => return None,
+                _ => {}
+            }
+        }
+        if feet.y < lowest {
+            return None;
+        }
+    }
+    None
+}
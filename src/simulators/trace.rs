@@ -0,0 +1,147 @@
+use std::fmt;
+
+/// block behaviors that change velocity mid-tick.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BlockEffect {
+    Web,
+    SoulSand,
+    HoneyBlock,
+    BubbleColumn,
+    SlimeBounce,
+    /// GenSimulator's getBlockSpeedFactor (soul sand, honey).
+    SpeedFactor,
+}
+
+/// One intermediate value a simulator computed during a tick.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TraceStage {
+    Start {
+        position: glam::Vec3A,
+        velocity: glam::Vec3A,
+    },
+    /// after fluid detection and current pushing.
+    Fluids {
+        in_water: bool,
+        in_lava: bool,
+        velocity: glam::Vec3A,
+    },
+    Jump {
+        velocity: glam::Vec3A,
+    },
+    /// movement impulses after sneaking/item use scaling.
+    Input {
+        strafe: f32,
+        forward: f32,
+    },
+    /// inertia and acceleration picked for this tick.
+    Friction {
+        inertia: f32,
+        acceleration: f32,
+    },
+    /// velocity after applying the input heading.
+    Heading {
+        velocity: glam::Vec3A,
+    },
+    StepUp {
+        candidate: glam::Vec3A,
+        taken: bool,
+    },
+    /// the move that was asked for and what collisions allowed.
+    Collision {
+        requested: glam::Vec3A,
+        allowed: glam::Vec3A,
+    },
+    BlockEffect {
+        effect: BlockEffect,
+        velocity: glam::Vec3A,
+    },
+    /// velocity after gravity and drag.
+    Drag {
+        velocity: glam::Vec3A,
+    },
+    End {
+        position: glam::Vec3A,
+        velocity: glam::Vec3A,
+        on_ground: bool,
+    },
+}
+
+impl fmt::Display for TraceStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Start { position, velocity } => {
+                write!(f, "start      pos {} vel {}", position, velocity)
+            }
+            Self::Fluids {
+                in_water,
+                in_lava,
+                velocity,
+            } => write!(
+                f,
+                "fluids     water {} lava {} vel {}",
+                in_water, in_lava, velocity
+            ),
+            Self::Jump { velocity } => write!(f, "jump       vel {}", velocity),
+            Self::Input { strafe, forward } => {
+                write!(f, "input      strafe {} forward {}", strafe, forward)
+            }
+            Self::Friction {
+                inertia,
+                acceleration,
+            } => write!(
+                f,
+                "friction   inertia {} acceleration {}",
+                inertia, acceleration
+            ),
+            Self::Heading { velocity } => write!(f, "heading    vel {}", velocity),
+            Self::StepUp { candidate, taken } => {
+                write!(f, "step up    candidate {} taken {}", candidate, taken)
+            }
+            Self::Collision { requested, allowed } => {
+                write!(f, "collision  requested {} allowed {}", requested, allowed)
+            }
+            Self::BlockEffect { effect, velocity } => {
+                write!(f, "block      {:?} vel {}", effect, velocity)
+            }
+            Self::Drag { velocity } => write!(f, "drag       vel {}", velocity),
+            Self::End {
+                position,
+                velocity,
+                on_ground,
+            } => write!(
+                f,
+                "end        pos {} vel {} on ground {}",
+                position, velocity, on_ground
+            ),
+        }
+    }
+}
+
+/// Every stage of one tick, in order. Printing it gives one stage per line.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TickTrace {
+    pub stages: Vec<TraceStage>,
+}
+
+impl TickTrace {
+    pub fn get_stages(&self) -> &[TraceStage] {
+        &self.stages
+    }
+
+    /// first stage matching `predicate`, e.g. `|s| matches!(s, TraceStage::Collision { .. })`.
+    pub fn find(&self, predicate: impl Fn(&TraceStage) -> bool) -> Option<&TraceStage> {
+        self.stages.iter().find(|stage| predicate(stage))
+    }
+}
+
+impl fmt::Display for TickTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for stage in self.stages.iter() {
+            writeln!(f, "{}", stage)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,361 @@
+use std::fmt;
+
+use serde_json::{json, Value};
+
+use crate::{
+    calc::angle::Rotation,
+    states::{
+        physics_context::EntityPhysicsContext,
+        player_context::{ControlStateHandler, PlayerPoses, TickInput},
+    },
+};
+
+use super::{Simulator, TickSummary, World};
+
+fn get_f32(value: &Value, key: &str) -> Result<f32, String> {
+    value
+        .get(key)
+        .and_then(Value::as_f64)
+        .map(|v| v as f32)
+        .ok_or_else(|| format!("missing \"{}\"", key))
+}
+
+fn get_bool(value: &Value, key: &str) -> Result<bool, String> {
+    value
+        .get(key)
+        .and_then(Value::as_bool)
+        .ok_or_else(|| format!("missing \"{}\"", key))
+}
+
+fn get_vec(value: &Value, key: &str) -> Result<glam::Vec3A, String> {
+    let arr = value
+        .get(key)
+        .and_then(Value::as_array)
+        .filter(|arr| arr.len() == 3)
+        .ok_or_else(|| format!("\"{}\" isn't [x, y, z]", key))?;
+    let mut v = [0.0; 3];
+    for (i, c) in arr.iter().enumerate() {
+        v[i] = c
+            .as_f64()
+            .ok_or_else(|| format!("\"{}\" isn't [x, y, z]", key))? as f32;
+    }
+    Ok(glam::Vec3A::from(v))
+}
+
+/// the state a trajectory starts from, so a recording can be replayed on its own.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrajectoryStart {
+    pub position: glam::Vec3A,
+    pub velocity: glam::Vec3A,
+    pub on_ground: bool,
+    pub rotation: Rotation,
+    pub pose: PlayerPoses,
+}
+
+impl TrajectoryStart {
+    pub fn from_ctx(ctx: &EntityPhysicsContext) -> Self {
+        Self {
+            position: ctx.state.position,
+            velocity: ctx.state.velocity,
+            on_ground: ctx.state.on_ground,
+            rotation: ctx.state.rotation,
+            pose: ctx.get_pose(),
+        }
+    }
+
+    /// puts `ctx` back into this state, the rest of it is left alone.
+    pub fn apply(&self, ctx: &mut EntityPhysicsContext) {
+        ctx.state.position = self.position;
+        ctx.state.velocity = self.velocity;
+        ctx.state.on_ground = self.on_ground;
+        ctx.state.rotation = self.rotation;
+        ctx.set_pose(self.pose);
+    }
+
+    fn to_json(self) -> Value {
+        json!({
+            "position": [self.position.x, self.position.y, self.position.z],
+            "velocity": [self.velocity.x, self.velocity.y, self.velocity.z],
+            "on_ground": self.on_ground,
+            "yaw": self.rotation.yaw,
+            "pitch": self.rotation.pitch,
+            "pose": self.pose.get_name(),
+        })
+    }
+
+    fn from_json(value: &Value) -> Result<Self, String> {
+        let pose = value
+            .get("pose")
+            .and_then(Value::as_str)
+            .ok_or_else(|| "missing \"pose\"".to_string())?;
+        Ok(Self {
+            position: get_vec(value, "position")?,
+            velocity: get_vec(value, "velocity")?,
+            on_ground: get_bool(value, "on_ground")?,
+            rotation: Rotation::new(get_f32(value, "yaw")?, get_f32(value, "pitch")?),
+            pose: PlayerPoses::from_name(pose)
+                .ok_or_else(|| format!("unknown pose \"{}\"", pose))?,
+        })
+    }
+}
+
+/// one recorded tick: the input it ran with and the state it ended in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrajectoryTick {
+    pub input: TickInput,
+    pub position: glam::Vec3A,
+    pub velocity: glam::Vec3A,
+    pub on_ground: bool,
+}
+
+impl TrajectoryTick {
+    fn from_ctx(input: TickInput, ctx: &EntityPhysicsContext) -> Self {
+        Self {
+            input,
+            position: ctx.state.position,
+            velocity: ctx.state.velocity,
+            on_ground: ctx.state.on_ground,
+        }
+    }
+
+    fn to_json(self) -> Value {
+        let c = &self.input.control_states;
+        json!({
+            "controls": {
+                "forward": c.forward,
+                "back": c.back,
+                "left": c.left,
+                "right": c.right,
+                "jump": c.jump,
+                "sprint": c.sprint,
+                "sneak": c.sneak,
+            },
+            "yaw": self.input.rotation.yaw,
+            "pitch": self.input.rotation.pitch,
+            "position": [self.position.x, self.position.y, self.position.z],
+            "velocity": [self.velocity.x, self.velocity.y, self.velocity.z],
+            "on_ground": self.on_ground,
+        })
+    }
+
+    fn from_json(value: &Value) -> Result<Self, String> {
+        let controls = value
+            .get("controls")
+            .ok_or_else(|| "missing \"controls\"".to_string())?;
+        // missing keys are released.
+        let key = |name: &str| controls.get(name).and_then(Value::as_bool).unwrap_or(false);
+
+        Ok(Self {
+            input: TickInput::new(
+                ControlStateHandler {
+                    forward: key("forward"),
+                    back: key("back"),
+                    left: key("left"),
+                    right: key("right"),
+                    jump: key("jump"),
+                    sprint: key("sprint"),
+                    sneak: key("sneak"),
+                },
+                Rotation::new(get_f32(value, "yaw")?, get_f32(value, "pitch")?),
+            ),
+            position: get_vec(value, "position")?,
+            velocity: get_vec(value, "velocity")?,
+            on_ground: get_bool(value, "on_ground")?,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum TrajectoryError {
+    /// line (1-based) that isn't json.
+    Json(usize, serde_json::Error),
+    /// line (1-based) that is json, but not a tick (or, for the first one, not a start).
+    Malformed(usize, String),
+    /// there's no line at all, not even the starting state.
+    MissingStart,
+}
+
+impl fmt::Display for TrajectoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(line, err) => write!(f, "line {}: invalid json: {}", line, err),
+            Self::Malformed(line, what) => write!(f, "line {}: malformed: {}", line, what),
+            Self::MissingStart => write!(f, "no starting state"),
+        }
+    }
+}
+
+impl std::error::Error for TrajectoryError {}
+
+/// where a replay first stopped matching its recording.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Divergence {
+    /// 0-based tick index.
+    pub tick: usize,
+    pub expected: TrajectoryTick,
+    pub actual: TrajectoryTick,
+}
+
+impl Divergence {
+    pub fn get_position_error(&self) -> f32 {
+        self.expected.position.distance(self.actual.position)
+    }
+
+    pub fn get_velocity_error(&self) -> f32 {
+        self.expected.velocity.distance(self.actual.velocity)
+    }
+}
+
+/// Per-tick inputs and resulting states of one entity.
+///
+/// Stored as JSON Lines so recordings can be diffed and streamed: the first line is
+/// the starting state, then one tick per line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trajectory {
+    start: TrajectoryStart,
+    ticks: Vec<TrajectoryTick>,
+}
+
+impl Trajectory {
+    pub fn new(start: TrajectoryStart) -> Self {
+        Self {
+            start,
+            ticks: vec![],
+        }
+    }
+
+    pub fn get_start(&self) -> &TrajectoryStart {
+        &self.start
+    }
+
+    pub fn push(&mut self, tick: TrajectoryTick) {
+        self.ticks.push(tick);
+    }
+
+    pub fn get_ticks(&self) -> &[TrajectoryTick] {
+        &self.ticks
+    }
+
+    pub fn len(&self) -> usize {
+        self.ticks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ticks.is_empty()
+    }
+
+    pub fn to_json_lines(&self) -> String {
+        let mut out = self.start.to_json().to_string();
+        out.push('\n');
+        for tick in self.ticks.iter() {
+            out.push_str(&tick.to_json().to_string());
+            out.push('\n');
+        }
+        out
+    }
+
+    /// blank lines are skipped.
+    pub fn from_json_lines(text: &str) -> Result<Self, TrajectoryError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str::<Value>(line)
+                    .map(|value| (i + 1, value))
+                    .map_err(|err| TrajectoryError::Json(i + 1, err))
+            });
+
+        let (line, value) = lines.next().ok_or(TrajectoryError::MissingStart)??;
+        let start = TrajectoryStart::from_json(&value)
+            .map_err(|what| TrajectoryError::Malformed(line, what))?;
+        let mut trajectory = Self::new(start);
+        for next in lines {
+            let (line, value) = next?;
+            let tick = TrajectoryTick::from_json(&value)
+                .map_err(|what| TrajectoryError::Malformed(line, what))?;
+            trajectory.push(tick);
+        }
+        Ok(trajectory)
+    }
+
+    /// puts `ctx` into the starting state and feeds it the recorded inputs.
+    /// `ctx` only has to be the same kind of entity, the recording doesn't hold its physics.
+    /// stops at the first tick whose position or velocity is off by more than `tolerance`
+    /// (or that disagrees on being on ground), none if the whole recording matches.
+    pub fn replay(
+        &self,
+        simulator: &impl Simulator,
+        ctx: &mut EntityPhysicsContext,
+        world: &impl World,
+        tolerance: f32,
+    ) -> Option<Divergence> {
+        self.start.apply(ctx);
+        for (tick, expected) in self.ticks.iter().enumerate() {
+            expected.input.apply(ctx);
+            simulator.tick(ctx, world);
+
+            let actual = TrajectoryTick::from_ctx(expected.input, ctx);
+            if actual.position.distance(expected.position) > tolerance
+                || actual.velocity.distance(expected.velocity) > tolerance
+                || actual.on_ground != expected.on_ground
+            {
+                return Some(Divergence {
+                    tick,
+                    expected: *expected,
+                    actual,
+                });
+            }
+        }
+        None
+    }
+}
+
+/// Records a trajectory while simulating.
+///
+/// Set the inputs on the context as usual (or pass a `TickInput`),
+/// then tick through the recorder instead of the simulator.
+pub struct TrajectoryRecorder {
+    trajectory: Trajectory,
+}
+
+impl TrajectoryRecorder {
+    /// starts recording from the current state of `ctx`.
+    pub fn new(ctx: &EntityPhysicsContext) -> Self {
+        Self {
+            trajectory: Trajectory::new(TrajectoryStart::from_ctx(ctx)),
+        }
+    }
+
+    /// ticks with whatever controls/yaw/pitch the context currently has.
+    pub fn tick(
+        &mut self,
+        simulator: &impl Simulator,
+        ctx: &mut EntityPhysicsContext,
+        world: &impl World,
+    ) -> TickSummary {
+        let input = TickInput::from_state(&ctx.state);
+        let summary = simulator.step(ctx, world);
+        self.trajectory.push(TrajectoryTick::from_ctx(input, ctx));
+        summary
+    }
+
+    pub fn tick_with(
+        &mut self,
+        simulator: &impl Simulator,
+        ctx: &mut EntityPhysicsContext,
+        input: TickInput,
+        world: &impl World,
+    ) -> TickSummary {
+        input.apply(ctx);
+        self.tick(simulator, ctx, world)
+    }
+
+    pub fn get_trajectory(&self) -> &Trajectory {
+        &self.trajectory
+    }
+
+    pub fn into_trajectory(self) -> Trajectory {
+        self.trajectory
+    }
+}
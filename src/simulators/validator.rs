@@ -0,0 +1,142 @@
+use crate::states::{
+    context_builder::EntityPhysicsContextBuilder, physics_context::EntityPhysicsContext,
+    EntityState,
+};
+
+use super::{inputs::InputCandidate, Simulator, World};
+
+/// how far (in blocks) a reported position may be from the closest prediction.
+pub const DEFAULT_TOLERANCE: f32 = 0.03;
+
+/// Why a move was rejected.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Violation {
+    /// moved `horizontal` blocks away from anything the inputs allow.
+    Speed { horizontal: f32 },
+    /// moved `vertical` blocks up (or down, if negative) from anything the inputs allow.
+    Fly { vertical: f32 },
+    /// claimed to be on ground while every matching prediction is still falling.
+    NoFall { fall_distance: f32 },
+}
+
+/// The closest prediction for one reported move.
+#[derive(Clone)]
+pub struct MoveVerdict {
+    pub input: InputCandidate,
+    pub predicted: EntityState,
+    /// reported minus predicted position.
+    pub offset: glam::Vec3A,
+    pub violation: Option<Violation>,
+}
+
+impl MoveVerdict {
+    pub fn is_valid(&self) -> bool {
+        self.violation.is_none()
+    }
+}
+
+/// Server-side movement checker.
+///
+/// Simulates every `InputCandidate` from the player's previous state and compares
+/// the client's reported position and on_ground flag with the closest result.
+/// Rotation comes from the previous state, set it to what the client sent for this tick.
+pub struct MovementValidator<S: Simulator> {
+    simulator: S,
+    tolerance: f32,
+}
+
+impl<S: Simulator> MovementValidator<S> {
+    pub fn new(simulator: S) -> Self {
+        Self {
+            simulator,
+            tolerance: DEFAULT_TOLERANCE,
+        }
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn get_simulator(&self) -> &S {
+        &self.simulator
+    }
+
+    pub fn get_tolerance(&self) -> f32 {
+        self.tolerance
+    }
+
+    /// checks a move of a default player.
+    pub fn check(
+        &self,
+        previous: &EntityState,
+        position: glam::Vec3A,
+        on_ground: bool,
+        world: &impl World,
+    ) -> MoveVerdict {
+        let mut ctx = EntityPhysicsContextBuilder::player()
+            .pose(previous.pose)
+            .state(previous.clone())
+            .build()
+            .expect("the player preset is valid");
+        self.check_ctx(&mut ctx, position, on_ground, world)
+    }
+
+    /// checks a move of `ctx`, for players with non-default physics.
+    /// `ctx` is left in its previous state.
+    pub fn check_ctx(
+        &self,
+        ctx: &mut EntityPhysicsContext,
+        position: glam::Vec3A,
+        on_ground: bool,
+        world: &impl World,
+    ) -> MoveVerdict {
+        let previous = ctx.state.clone();
+
+        // within tolerance and agreeing on ground first, then closest.
+        let mut best: Option<((bool, f32), InputCandidate, EntityState)> = None;
+        for candidate in InputCandidate::all() {
+            ctx.state = previous.clone();
+            candidate.apply(ctx);
+            self.simulator.tick(ctx, world);
+
+            let error = ctx.state.position.distance(position);
+            // claiming to be in the air never helps a cheater.
+            let ground_ok = !on_ground || ctx.state.on_ground;
+            let score = (error > self.tolerance || !ground_ok, error);
+            let better = match &best {
+                Some((best_score, _, _)) => score < *best_score,
+                None => true,
+            };
+            if better {
+                best = Some((score, candidate, ctx.state.clone()));
+            }
+        }
+        ctx.state = previous;
+
+        let (_, input, predicted) = best.expect("there's always a candidate");
+        let offset = position - predicted.position;
+        let horizontal = glam::Vec2::new(offset.x, offset.z).length();
+
+        let violation = if offset.length() <= self.tolerance {
+            if on_ground && !predicted.on_ground {
+                Some(Violation::NoFall {
+                    fall_distance: predicted.fall_distance,
+                })
+            } else {
+                None
+            }
+        } else if offset.y.abs() > horizontal {
+            Some(Violation::Fly { vertical: offset.y })
+        } else {
+            Some(Violation::Speed { horizontal })
+        };
+
+        MoveVerdict {
+            input,
+            predicted,
+            offset,
+            violation,
+        }
+    }
+}
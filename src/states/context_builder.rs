@@ -0,0 +1,243 @@
+use std::fmt;
+
+use crate::{calc::angle::Rotation, settings::physics_settings};
+
+use super::{
+    physics_context::{CollisionBehavior, EntityPhysicsContext, EntityType},
+    player_context::PlayerPoses,
+    EntityState,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContextError {
+    /// a physics parameter that's negative, not finite or (for inertias/drag) above 1.
+    OutOfRange { field: &'static str, value: f32 },
+    /// non-player entities need a width and height for their bounding box.
+    MissingSize,
+    /// entity type can't be empty, it decides what counts as a player/living entity.
+    MissingType,
+}
+
+impl fmt::Display for ContextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfRange { field, value } => write!(f, "{} out of range: {}", field, value),
+            Self::MissingSize => write!(f, "non-player entities need a width and height"),
+            Self::MissingType => write!(f, "entity type is empty"),
+        }
+    }
+}
+
+impl std::error::Error for ContextError {}
+
+/// Named alternative to `EntityPhysicsContext::raw`.
+///
+/// Start from a preset (or `new` for a blank entity), override what differs, then `build`.
+/// ```no_run
+/// # use minecraft_physics::states::context_builder::{ContextError, EntityPhysicsContextBuilder};
+/// # fn main() -> Result<(), ContextError> {
+/// let ctx = EntityPhysicsContextBuilder::player()
+///     .position(glam::Vec3A::new(0.5, 64.0, 0.5))
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct EntityPhysicsContextBuilder {
+    ctx: EntityPhysicsContext,
+}
+
+impl EntityPhysicsContextBuilder {
+    /// no gravity, no drag, no size. mostly useful for fully custom entities.
+    pub fn new(e_type: &str, name: &str) -> Self {
+        Self {
+            ctx: EntityPhysicsContext {
+                entity_type: EntityType::new(e_type.to_string(), name.to_string(), None, None),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// original: LivingEntity, the values every mob and player share.
+    fn living(e_type: &str, name: &str) -> Self {
+        Self::new(e_type, name)
+            .step_height(0.6)
+            .gravity(0.08)
+            .water_gravity(0.08 / 16.0)
+            .water_inertia(physics_settings::WATER_INERTIA)
+            .lava_gravity(0.08 / 4.0)
+            .lava_inertia(physics_settings::LAVA_INERTIA)
+            .airdrag(0.98)
+            .gravity_then_drag(true)
+    }
+
+    /// a controllable player, sized by its pose.
+    pub fn player() -> Self {
+        Self::living("player", "player").use_controls(true)
+    }
+
+    pub fn mob(name: &str, width: f32, height: f32) -> Self {
+        Self::living("mob", name).size(width, height)
+    }
+
+    /// a dropped item, approximately: item sized, with item gravity and drag.
+    /// the simulators still move it like a living entity (without controls),
+    /// so it won't slide or bounce the way real items do.
+    pub fn item() -> Self {
+        Self::new("other", "item")
+            .size(0.25, 0.25)
+            .gravity(0.04)
+            .water_gravity(0.04 / 16.0)
+            .water_inertia(physics_settings::WATER_INERTIA)
+            .lava_gravity(0.04 / 4.0)
+            .lava_inertia(physics_settings::LAVA_INERTIA)
+            .airdrag(0.98)
+            .gravity_then_drag(true)
+    }
+
+    /// an arrow-like projectile, approximately: drag before gravity, no stepping.
+    /// it's moved like any other entity, nothing sticks or stops on impact.
+    /// for something closer to thrown items, lower the gravity.
+    pub fn projectile(name: &str, width: f32, height: f32) -> Self {
+        Self::new("projectile", name)
+            .size(width, height)
+            .collision_behavior(false, false)
+            .gravity(0.05)
+            .water_gravity(0.05)
+            .water_inertia(0.6)
+            .lava_gravity(0.05)
+            .lava_inertia(0.6)
+            .airdrag(0.99)
+            .gravity_then_drag(false)
+    }
+
+    pub fn state(mut self, state: EntityState) -> Self {
+        self.ctx.state = state;
+        self
+    }
+
+    pub fn position(mut self, position: glam::Vec3A) -> Self {
+        self.ctx.state.position = position;
+        self
+    }
+
+    pub fn velocity(mut self, velocity: glam::Vec3A) -> Self {
+        self.ctx.state.velocity = velocity;
+        self
+    }
+
+    /// takes either convention, see `Rotation` and `NotchianRotation`.
+    pub fn rotation(mut self, rotation: impl Into<Rotation>) -> Self {
+        self.ctx.state.rotation = rotation.into();
+        self
+    }
+
+    pub fn on_ground(mut self, on_ground: bool) -> Self {
+        self.ctx.state.on_ground = on_ground;
+        self
+    }
+
+    /// ignored for players, their size comes from the pose.
+    pub fn size(mut self, width: f32, height: f32) -> Self {
+        self.ctx.entity_type.width = Some(width);
+        self.ctx.entity_type.height = Some(height);
+        self
+    }
+
+    pub fn collision_behavior(
+        mut self,
+        block_effects: bool,
+        affected_after_collision: bool,
+    ) -> Self {
+        self.ctx.collision_behavior =
+            CollisionBehavior::new(block_effects, affected_after_collision);
+        self
+    }
+
+    pub fn pose(mut self, pose: PlayerPoses) -> Self {
+        self.ctx.pose = pose;
+        self.ctx.state.pose = pose;
+        self
+    }
+
+    pub fn use_controls(mut self, use_controls: bool) -> Self {
+        self.ctx.use_controls = use_controls;
+        self
+    }
+
+    pub fn step_height(mut self, step_height: f32) -> Self {
+        self.ctx.step_height = step_height;
+        self
+    }
+
+    pub fn gravity(mut self, gravity: f32) -> Self {
+        self.ctx.gravity = gravity;
+        self
+    }
+
+    pub fn water_gravity(mut self, water_gravity: f32) -> Self {
+        self.ctx.water_gravity = water_gravity;
+        self
+    }
+
+    pub fn water_inertia(mut self, water_inertia: f32) -> Self {
+        self.ctx.water_inertia = water_inertia;
+        self
+    }
+
+    pub fn lava_gravity(mut self, lava_gravity: f32) -> Self {
+        self.ctx.lava_gravity = lava_gravity;
+        self
+    }
+
+    pub fn lava_inertia(mut self, lava_inertia: f32) -> Self {
+        self.ctx.lava_inertia = lava_inertia;
+        self
+    }
+
+    pub fn airdrag(mut self, airdrag: f32) -> Self {
+        self.ctx.airdrag = airdrag;
+        self
+    }
+
+    pub fn gravity_then_drag(mut self, gravity_then_drag: bool) -> Self {
+        self.ctx.gravity_then_drag = gravity_then_drag;
+        self
+    }
+
+    pub fn build(self) -> Result<EntityPhysicsContext, ContextError> {
+        let mut ctx = self.ctx;
+        if ctx.entity_type.e_type.is_empty() {
+            return Err(ContextError::MissingType);
+        }
+        if !ctx.is_player() {
+            match (ctx.entity_type.width, ctx.entity_type.height) {
+                (Some(width), Some(height)) => {
+                    check_range("width", width, f32::MAX)?;
+                    check_range("height", height, f32::MAX)?;
+                }
+                _ => return Err(ContextError::MissingSize),
+            }
+        }
+
+        check_range("step_height", ctx.step_height, f32::MAX)?;
+        check_range("gravity", ctx.gravity, f32::MAX)?;
+        check_range("water_gravity", ctx.water_gravity, f32::MAX)?;
+        check_range("lava_gravity", ctx.lava_gravity, f32::MAX)?;
+        check_range("water_inertia", ctx.water_inertia, 1.0)?;
+        check_range("lava_inertia", ctx.lava_inertia, 1.0)?;
+        check_range("airdrag", ctx.airdrag, 1.0)?;
+
+        // the state keeps its own copy of the size.
+        ctx.state.height = ctx.get_height();
+        ctx.state.half_width = ctx.get_half_width();
+        Ok(ctx)
+    }
+}
+
+fn check_range(field: &'static str, value: f32, max: f32) -> Result<(), ContextError> {
+    if value.is_finite() && value >= 0.0 && value <= max {
+        Ok(())
+    } else {
+        Err(ContextError::OutOfRange { field, value })
+    }
+}
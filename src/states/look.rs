@@ -0,0 +1,158 @@
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+use crate::{calc::angle::Rotation, settings::physics_settings};
+
+use super::{player_context::TickInput, EntityState};
+
+/// the options menu's 100%, vanilla defaults to half of it.
+pub const DEFAULT_SENSITIVITY: f32 = 0.5;
+/// seconds per tick.
+const TICK_SECONDS: f32 = 0.05;
+
+/// smallest rotation (radians) one mouse count makes at `sensitivity` (0 to 1).
+/// original: MouseHandler.turnPlayer, f = s * 0.6 + 0.2, f³ * 8 degrees, times 0.15 per count.
+pub fn get_rotation_gcd(sensitivity: f32) -> f32 {
+    let f = sensitivity * 0.6 + 0.2;
+    (f * f * f * 8.0 * 0.15).to_radians()
+}
+
+/// `to - from`, the short way round.
+fn yaw_difference(from: f32, to: f32) -> f32 {
+    let diff = (to - from).rem_euclid(TAU);
+    if diff > PI {
+        diff - TAU
+    } else {
+        diff
+    }
+}
+
+/// Turns towards a target rotation at a bounded rate, like a player moving a mouse would.
+///
+/// Per tick, yaw and pitch move at most `YAW_SPEED`/`PITCH_SPEED` (radians per second)
+/// times 0.05. With a sensitivity set, every change is a whole number of mouse counts,
+/// so the rotations are ones a vanilla client can actually send.
+/// Yaw is never wrapped, it keeps counting past ±π the way the client's does.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LookController {
+    yaw_speed: f32,
+    pitch_speed: f32,
+    sensitivity: Option<f32>,
+    target: Option<Rotation>,
+}
+
+impl Default for LookController {
+    fn default() -> Self {
+        Self {
+            yaw_speed: physics_settings::YAW_SPEED,
+            pitch_speed: physics_settings::PITCH_SPEED,
+            sensitivity: Some(DEFAULT_SENSITIVITY),
+            target: None,
+        }
+    }
+}
+
+impl LookController {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// radians per second.
+    pub fn with_speeds(mut self, yaw_speed: f32, pitch_speed: f32) -> Self {
+        self.yaw_speed = yaw_speed;
+        self.pitch_speed = pitch_speed;
+        self
+    }
+
+    /// none turns off snapping.
+    pub fn with_sensitivity(mut self, sensitivity: Option<f32>) -> Self {
+        self.sensitivity = sensitivity;
+        self
+    }
+
+    pub fn get_sensitivity(&self) -> Option<f32> {
+        self.sensitivity
+    }
+
+    /// see `get_rotation_gcd`, none without snapping.
+    pub fn get_gcd(&self) -> Option<f32> {
+        self.sensitivity.map(get_rotation_gcd)
+    }
+
+    pub fn get_target(&self) -> Option<Rotation> {
+        self.target
+    }
+
+    /// takes either convention, see `Rotation` and `NotchianRotation`.
+    pub fn look_at(&mut self, rotation: impl Into<Rotation>) {
+        let rotation = rotation.into();
+        self.target = Some(Rotation::new(
+            rotation.yaw,
+            rotation.pitch.clamp(-FRAC_PI_2, FRAC_PI_2),
+        ));
+    }
+
+    /// look from `eye` at `point`.
+    pub fn look_towards(&mut self, eye: glam::Vec3A, point: glam::Vec3A) {
+        self.look_at(Rotation::from_direction(point - eye));
+    }
+
+    pub fn clear_target(&mut self) {
+        self.target = None;
+    }
+
+    /// the rotation one tick after `from` when heading for `target`.
+    pub fn step(&self, from: Rotation, target: Rotation) -> Rotation {
+        let max_yaw = self.yaw_speed * TICK_SECONDS;
+        let max_pitch = self.pitch_speed * TICK_SECONDS;
+        let d_yaw = self.snap(yaw_difference(from.yaw, target.yaw), max_yaw);
+        let d_pitch = self.snap(target.pitch - from.pitch, max_pitch);
+        Rotation::new(
+            from.yaw + d_yaw,
+            (from.pitch + d_pitch).clamp(-FRAC_PI_2, FRAC_PI_2),
+        )
+    }
+
+    /// no further step gets closer to the target.
+    pub fn is_facing(&self, from: Rotation, target: Rotation) -> bool {
+        self.step(from, target) == from
+    }
+
+    /// turns `state` one tick towards the target, forgetting the target once there.
+    /// returns whether it's facing the target (or there's none).
+    pub fn tick(&mut self, state: &mut EntityState) -> bool {
+        let target = match self.target {
+            Some(target) => target,
+            None => return true,
+        };
+        let rotation = self.step(state.rotation, target);
+        state.rotation = rotation;
+        if self.is_facing(rotation, target) {
+            self.target = None;
+            return true;
+        }
+        false
+    }
+
+    /// `input` with its rotation turned into one step from `state`'s, for controllers that
+    /// just say where they want to look.
+    pub fn smooth(&self, state: &EntityState, input: TickInput) -> TickInput {
+        TickInput {
+            rotation: self.step(state.rotation, input.rotation),
+            ..input
+        }
+    }
+
+    /// `delta` limited to `max`, in whole mouse counts when snapping.
+    fn snap(&self, delta: f32, max: f32) -> f32 {
+        let delta = delta.clamp(-max, max);
+        let gcd = match self.get_gcd() {
+            Some(gcd) => gcd,
+            None => return delta,
+        };
+        let mut counts = (delta / gcd).round();
+        if (counts * gcd).abs() > max {
+            counts -= counts.signum();
+        }
+        counts * gcd
+    }
+}
@@ -1,11 +1,15 @@
+pub mod context_builder;
+pub mod look;
 pub mod physics_context;
 pub mod player_context;
+#[cfg(feature = "serde")]
+pub mod snapshot;
 
 use std::collections::HashMap;
 
 use inter_struct::prelude::*;
 
-use crate::{settings::PlayerAttribute, calc::aabb::AABB};
+use crate::{settings::PlayerAttribute, calc::{aabb::AABB, angle::Rotation}};
 
 use self::player_context::{PlayerPoses, ControlStateHandler};
 
@@ -14,17 +18,20 @@ use self::player_context::{PlayerPoses, ControlStateHandler};
 /// Don't want to do merge rn. Lazy.
 #[derive(Clone, Default, StructMergeRef)]
 #[struct_merge_ref("crate::states::EntityState")]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct EntityState {
     pub height: f32,
     pub half_width: f32,
     pub position: glam::Vec3A,
     pub velocity: glam::Vec3A,
 
-    pub pitch: f32,
-    pub yaw: f32,
+    pub rotation: Rotation,
 
     /// perhaps merge with is_collided_vertically.
     pub on_ground: bool,
+    /// blocks fallen since last on ground, reset by water.
+    pub fall_distance: f32,
 
     pub age: u64,
     pub is_in_water: bool,
@@ -36,6 +43,10 @@ pub struct EntityState {
 
     pub sneak_collision: bool,
 
+    /// fell out of the world (BELOW_WORLD_DEPTH under the world's min y).
+    /// vanilla kills/removes the entity, we just flag it.
+    pub is_below_world: bool,
+
     /// note: it doesn't make sense for self to be above 255.
     pub jump_ticks: u8,
     pub jump_queued: bool,
@@ -74,21 +85,20 @@ pub struct EntityState {
 impl EntityState {
 
     /// original new function.
-    pub fn new(height: f32, half_width: f32, position: glam::Vec3A, velocity: glam::Vec3A, on_ground: bool, yaw: f32, pitch: f32) -> Self {
+    pub fn new(height: f32, half_width: f32, position: glam::Vec3A, velocity: glam::Vec3A, on_ground: bool, rotation: Rotation) -> Self {
         Self {
             height,
             half_width,
             position,
             velocity,
             on_ground,
-            yaw,
-            pitch,
+            rotation,
             ..Default::default()
         }
     }
 
     /// new pose, use PlayerPoses for height and width.
-    pub fn new_by_pose(pose: PlayerPoses, position: glam::Vec3A, velocity: glam::Vec3A, on_ground: bool, yaw: f32, pitch: f32) -> Self {
+    pub fn new_by_pose(pose: PlayerPoses, position: glam::Vec3A, velocity: glam::Vec3A, on_ground: bool, rotation: Rotation) -> Self {
         let tmp = pose.get_info();
         Self {
             pose,
@@ -97,8 +107,7 @@ impl EntityState {
             position,
             velocity,
             on_ground,
-            yaw,
-            pitch,
+            rotation,
             ..Default::default()
         }
     }
@@ -1,6 +1,16 @@
 
-use crate::{calc::aabb::AABB, states::player_context::PlayerPoses, states::EntityState};
+use crate::{
+    calc::aabb::AABB,
+    simulators::{
+        events::PhysicsEvent,
+        trace::{TickTrace, TraceStage},
+    },
+    states::player_context::PlayerPoses,
+    states::EntityState,
+};
 
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CollisionBehavior {
     pub(crate) block_effects: bool,
     pub(crate) affected_after_collision: bool,
@@ -26,7 +36,8 @@ impl Default for CollisionBehavior {
 }
 
 /// placeholder.
-#[derive(Default)]
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EntityType {
     /// original: "type"
     pub(crate) e_type: String,
@@ -46,7 +57,8 @@ impl EntityType {
     }
 }
 
-#[derive(Default)]
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EntityPhysicsContext {
     pub(crate) state: EntityState,
 
@@ -64,6 +76,13 @@ pub struct EntityPhysicsContext {
     pub(crate) lava_inertia: f32,
     pub(crate) airdrag: f32,
     pub(crate) gravity_then_drag: bool,
+
+    /// only collected while ticking through `Simulator::tick_with_events`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) events: Option<Vec<PhysicsEvent>>,
+    /// only collected while ticking through `Simulator::tick_with_trace`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) trace: Option<TickTrace>,
 }
 
 impl EntityPhysicsContext {
@@ -96,6 +115,8 @@ impl EntityPhysicsContext {
             lava_inertia,
             airdrag,
             gravity_then_drag,
+            events: None,
+            trace: None,
         }
     }
 
@@ -106,10 +127,132 @@ impl EntityPhysicsContext {
         }
     }
 
+    /// records an event, if anyone is listening.
+    pub(crate) fn emit(&mut self, event: PhysicsEvent) {
+        if let Some(events) = self.events.as_mut() {
+            events.push(event);
+        }
+    }
+
+    /// records a debug stage, if tracing.
+    pub(crate) fn record(&mut self, stage: TraceStage) {
+        if let Some(trace) = self.trace.as_mut() {
+            trace.stages.push(stage);
+        }
+    }
+
     pub fn get_state(&self) -> &EntityState {
         &self.state
     }
 
+    pub fn get_state_mut(&mut self) -> &mut EntityState {
+        &mut self.state
+    }
+
+    pub fn get_entity_type(&self) -> &str {
+        &self.entity_type.e_type
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.entity_type.name
+    }
+
+    pub fn get_pose(&self) -> PlayerPoses {
+        self.pose
+    }
+
+    /// players are sized by their pose, so this resizes them too.
+    pub fn set_pose(&mut self, pose: PlayerPoses) {
+        self.pose = pose;
+        self.state.pose = pose;
+        self.state.height = self.get_height();
+        self.state.half_width = self.get_half_width();
+    }
+
+    pub fn get_step_height(&self) -> f32 {
+        self.step_height
+    }
+
+    pub fn set_step_height(&mut self, step_height: f32) {
+        self.step_height = step_height;
+    }
+
+    pub fn get_gravity(&self) -> f32 {
+        self.gravity
+    }
+
+    pub fn set_gravity(&mut self, gravity: f32) {
+        self.gravity = gravity;
+    }
+
+    pub fn get_water_gravity(&self) -> f32 {
+        self.water_gravity
+    }
+
+    pub fn set_water_gravity(&mut self, water_gravity: f32) {
+        self.water_gravity = water_gravity;
+    }
+
+    pub fn get_water_inertia(&self) -> f32 {
+        self.water_inertia
+    }
+
+    pub fn set_water_inertia(&mut self, water_inertia: f32) {
+        self.water_inertia = water_inertia;
+    }
+
+    pub fn get_lava_gravity(&self) -> f32 {
+        self.lava_gravity
+    }
+
+    pub fn set_lava_gravity(&mut self, lava_gravity: f32) {
+        self.lava_gravity = lava_gravity;
+    }
+
+    pub fn get_lava_inertia(&self) -> f32 {
+        self.lava_inertia
+    }
+
+    pub fn set_lava_inertia(&mut self, lava_inertia: f32) {
+        self.lava_inertia = lava_inertia;
+    }
+
+    pub fn get_airdrag(&self) -> f32 {
+        self.airdrag
+    }
+
+    pub fn set_airdrag(&mut self, airdrag: f32) {
+        self.airdrag = airdrag;
+    }
+
+    pub fn get_gravity_then_drag(&self) -> bool {
+        self.gravity_then_drag
+    }
+
+    pub fn set_gravity_then_drag(&mut self, gravity_then_drag: bool) {
+        self.gravity_then_drag = gravity_then_drag;
+    }
+
+    pub fn get_use_controls(&self) -> bool {
+        self.use_controls
+    }
+
+    pub fn set_use_controls(&mut self, use_controls: bool) {
+        self.use_controls = use_controls;
+    }
+
+    pub fn is_player(&self) -> bool {
+        self.entity_type.e_type == "player"
+    }
+
+    /// minecraft-data's living entity types.
+    pub fn is_living(&self) -> bool {
+        matches!(
+            self.entity_type.e_type.as_str(),
+            "player" | "mob" | "living" | "animal" | "hostile" | "passive" | "water_creature" | "ambient"
+        )
+    }
+
     pub fn get_width(&self) -> f32 {
         if self.entity_type.e_type == "player" {
             // potential performance penalty due to self.pose needing copy (not zero-cost).
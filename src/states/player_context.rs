@@ -1,3 +1,5 @@
+use crate::calc::angle::Rotation;
+
 
 #[derive(Clone, Copy)]
 pub struct WidthAndHeight {
@@ -43,7 +45,8 @@ pub const PLAYER_POSE_CONTEXT: [WidthAndHeight; 8] = [
 /// I'm not sure whether or not self is a performance issue.
 /// I'll look into it later.
 #[repr(usize)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PlayerPoses {
     Standing,
     FallFlying,
@@ -66,9 +69,38 @@ impl PlayerPoses {
     pub fn get_info(self) -> WidthAndHeight {
         return PLAYER_POSE_CONTEXT[self as usize];
     }
+
+    /// snake_case name of the variant, for text formats.
+    pub fn get_name(self) -> &'static str {
+        match self {
+            Self::Standing => "standing",
+            Self::FallFlying => "fall_flying",
+            Self::Sleeping => "sleeping",
+            Self::Swimming => "swimming",
+            Self::SpinAttack => "spin_attack",
+            Self::Sneaking => "sneaking",
+            Self::LongJumping => "long_jumping",
+            Self::Dying => "dying",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "standing" => Self::Standing,
+            "fall_flying" => Self::FallFlying,
+            "sleeping" => Self::Sleeping,
+            "swimming" => Self::Swimming,
+            "spin_attack" => Self::SpinAttack,
+            "sneaking" => Self::Sneaking,
+            "long_jumping" => Self::LongJumping,
+            "dying" => Self::Dying,
+            _ => return None,
+        })
+    }
 }
 
-#[derive(Default, Clone, Copy, PartialEq, Eq)]
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ControlStateHandler {
     pub forward: bool,
     pub back: bool,
@@ -79,4 +111,32 @@ pub struct ControlStateHandler {
     pub sneak: bool,
 }
 
-impl ControlStateHandler {}
\ No newline at end of file
+impl ControlStateHandler {}
+
+/// everything a player decides in one tick.
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TickInput {
+    pub control_states: ControlStateHandler,
+    pub rotation: Rotation,
+}
+
+impl TickInput {
+    /// takes either convention, see `Rotation` and `NotchianRotation`.
+    pub fn new(control_states: ControlStateHandler, rotation: impl Into<Rotation>) -> Self {
+        Self {
+            control_states,
+            rotation: rotation.into(),
+        }
+    }
+
+    pub fn from_state(state: &super::EntityState) -> Self {
+        Self::new(state.control_states, state.rotation)
+    }
+
+    /// sets up the entity for the next tick.
+    pub fn apply(&self, ctx: &mut super::physics_context::EntityPhysicsContext) {
+        ctx.state.control_states = self.control_states;
+        ctx.state.rotation = self.rotation;
+    }
+}
@@ -0,0 +1,74 @@
+use std::fmt;
+
+use super::physics_context::EntityPhysicsContext;
+
+/// bump when a field changes meaning or gets renamed/removed.
+/// missing state fields fall back to their defaults, but every physics field of a context
+/// has to be there: a gravity of 0 is a valid value, so it can't be a silent default.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Json(serde_json::Error),
+    /// written by a different schema version.
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(err) => write!(f, "invalid snapshot: {}", err),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "snapshot schema version {} isn't supported (expected {})",
+                version, SCHEMA_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<serde_json::Error> for SnapshotError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+/// only used to check the version before parsing the rest.
+#[derive(serde::Deserialize)]
+struct VersionOnly {
+    version: u32,
+}
+
+/// A versioned set of entities, e.g. a whole simulation or a test fixture.
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    pub contexts: Vec<EntityPhysicsContext>,
+}
+
+impl Snapshot {
+    pub fn new(contexts: Vec<EntityPhysicsContext>) -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            contexts,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, SnapshotError> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn to_json_pretty(&self) -> Result<String, SnapshotError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, SnapshotError> {
+        let version: VersionOnly = serde_json::from_str(json)?;
+        if version.version != SCHEMA_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version.version));
+        }
+        Ok(serde_json::from_str(json)?)
+    }
+}
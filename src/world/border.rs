@@ -0,0 +1,126 @@
+use crate::calc::aabb::AABB;
+
+/// original: WorldBorder, the moving (lerping) part is tick based instead of ms based.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WorldBorder {
+    pub center_x: f32,
+    pub center_z: f32,
+    pub lerp_from: f32,
+    pub lerp_target: f32,
+    pub lerp_duration: u64,
+    pub lerp_progress: u64,
+}
+
+impl Default for WorldBorder {
+    /// vanilla default, 59999968 wide around 0 0.
+    fn default() -> Self {
+        Self::new(0.0, 0.0, 59999968.0)
+    }
+}
+
+impl WorldBorder {
+    pub fn new(center_x: f32, center_z: f32, size: f32) -> Self {
+        Self {
+            center_x,
+            center_z,
+            lerp_from: size,
+            lerp_target: size,
+            lerp_duration: 0,
+            lerp_progress: 0,
+        }
+    }
+
+    /// starts moving from the current size to `target` over `ticks`.
+    pub fn lerp_to(mut self, target: f32, ticks: u64) -> Self {
+        self.lerp_from = self.get_size();
+        self.lerp_target = target;
+        self.lerp_duration = ticks;
+        self.lerp_progress = 0;
+        self
+    }
+
+    pub fn tick(&mut self) {
+        if self.lerp_progress < self.lerp_duration {
+            self.lerp_progress += 1;
+        }
+    }
+
+    pub fn is_moving(&self) -> bool {
+        self.lerp_progress < self.lerp_duration
+    }
+
+    /// current diameter.
+    pub fn get_size(&self) -> f32 {
+        if self.lerp_duration == 0 {
+            return self.lerp_target;
+        }
+        let progress = self.lerp_progress as f32 / self.lerp_duration as f32;
+        self.lerp_from + (self.lerp_target - self.lerp_from) * progress
+    }
+
+    pub fn get_min_x(&self) -> f32 {
+        self.center_x - self.get_size() / 2.0
+    }
+
+    pub fn get_max_x(&self) -> f32 {
+        self.center_x + self.get_size() / 2.0
+    }
+
+    pub fn get_min_z(&self) -> f32 {
+        self.center_z - self.get_size() / 2.0
+    }
+
+    pub fn get_max_z(&self) -> f32 {
+        self.center_z + self.get_size() / 2.0
+    }
+
+    pub fn is_within_bounds(&self, x: f32, z: f32, margin: f32) -> bool {
+        x >= self.get_min_x() - margin
+            && x < self.get_max_x() + margin
+            && z >= self.get_min_z() - margin
+            && z < self.get_max_z() + margin
+    }
+
+    pub fn get_distance_to_border(&self, x: f32, z: f32) -> f32 {
+        let dx = (x - self.get_min_x()).min(self.get_max_x() - x);
+        let dz = (z - self.get_min_z()).min(self.get_max_z() - z);
+        dx.min(dz)
+    }
+
+    /// original: isInsideCloseToBorder.
+    /// entities outside the border (or far from it) don't collide with it.
+    pub fn is_inside_close_to_border(&self, entity_bb: &AABB) -> bool {
+        let x = (entity_bb.min_x + entity_bb.max_x) / 2.0;
+        let z = (entity_bb.min_z + entity_bb.max_z) / 2.0;
+        let size = (entity_bb.max_x - entity_bb.min_x)
+            .max(entity_bb.max_z - entity_bb.min_z)
+            .max(1.0);
+        self.get_distance_to_border(x, z) < size * 2.0 && self.is_within_bounds(x, z, size)
+    }
+
+    /// the border as four walls, cut down to what overlaps `query_bb`.
+    pub fn get_collision_bbs(&self, entity_bb: &AABB, query_bb: &AABB) -> Vec<AABB> {
+        let mut walls = vec![];
+        if !self.is_inside_close_to_border(entity_bb) {
+            return walls;
+        }
+
+        let (min_x, max_x) = (self.get_min_x(), self.get_max_x());
+        let (min_z, max_z) = (self.get_min_z(), self.get_max_z());
+        let (low_y, high_y) = (query_bb.min_y - 1.0, query_bb.max_y + 1.0);
+        let (low_x, high_x) = (query_bb.min_x - 1.0, query_bb.max_x + 1.0);
+        let (low_z, high_z) = (query_bb.min_z - 1.0, query_bb.max_z + 1.0);
+
+        for wall in [
+            AABB::new(low_x.min(min_x - 1.0), low_y, low_z, min_x, high_y, high_z),
+            AABB::new(max_x, low_y, low_z, high_x.max(max_x + 1.0), high_y, high_z),
+            AABB::new(low_x, low_y, low_z.min(min_z - 1.0), high_x, high_y, min_z),
+            AABB::new(low_x, low_y, max_z, high_x, high_y, high_z.max(max_z + 1.0)),
+        ] {
+            if wall.intersects(query_bb) {
+                walls.push(wall);
+            }
+        }
+        walls
+    }
+}
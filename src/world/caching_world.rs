@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    RwLock,
+};
+
+use crate::{
+    calc::aabb::AABB,
+    simulators::{Block, World},
+    world::border::WorldBorder,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CacheStats {
+    pub fn get_hit_rate(&self) -> f32 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            return 0.0;
+        }
+        self.hits as f32 / total as f32
+    }
+}
+
+/// Memoizes `get_block` of any world.
+///
+/// Lookups are keyed by block coordinates, so the inner world has to answer
+/// the same block for every position inside it (all sane worlds do).
+///
+/// Two ways to use it:
+/// - per tick: call `clear` between ticks, for worlds that change.
+/// - per region: `with_region`, only positions inside are cached and kept until cleared.
+///
+/// Sync as long as the inner world is, so one cache can be shared by a `BatchSimulator`.
+pub struct CachingWorld<W: World> {
+    world: W,
+    region: Option<AABB>,
+    cache: RwLock<HashMap<(i32, i32, i32), Option<Block>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<W: World> CachingWorld<W> {
+    pub fn new(world: W) -> Self {
+        Self {
+            world,
+            region: None,
+            cache: RwLock::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn with_region(mut self, region: AABB) -> Self {
+        self.region = Some(region);
+        self
+    }
+
+    pub fn set_region(&mut self, region: Option<AABB>) {
+        self.region = region;
+        self.get_cache_mut().clear();
+    }
+
+    /// drops every cached block. call between ticks if the world changes.
+    pub fn clear(&mut self) {
+        self.get_cache_mut().clear();
+    }
+
+    /// drops one cached block, e.g. after a block update.
+    pub fn invalidate(&mut self, pos: &glam::Vec3A) {
+        let key = Self::get_key(pos);
+        self.get_cache_mut().remove(&key);
+    }
+
+    pub fn get_stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn reset_stats(&self) {
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+    }
+
+    pub fn get_inner(&self) -> &W {
+        &self.world
+    }
+
+    pub fn into_inner(self) -> W {
+        self.world
+    }
+
+    /// a panic while holding the lock can't leave the map half written, so poisoning is ignored.
+    fn get_cache_mut(&mut self) -> &mut HashMap<(i32, i32, i32), Option<Block>> {
+        self.cache.get_mut().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn get_key(pos: &glam::Vec3A) -> (i32, i32, i32) {
+        let pos = pos.floor();
+        (pos.x as i32, pos.y as i32, pos.z as i32)
+    }
+
+    fn is_cacheable(&self, pos: &glam::Vec3A) -> bool {
+        match &self.region {
+            Some(region) => {
+                pos.x >= region.min_x
+                    && pos.x < region.max_x
+                    && pos.y >= region.min_y
+                    && pos.y < region.max_y
+                    && pos.z >= region.min_z
+                    && pos.z < region.max_z
+            }
+            None => true,
+        }
+    }
+}
+
+impl<W: World> World for CachingWorld<W> {
+    fn get_block(&self, pos: &glam::Vec3A) -> Option<Block> {
+        if !self.is_cacheable(pos) {
+            return self.world.get_block(pos);
+        }
+
+        let key = Self::get_key(pos);
+        let cached = self
+            .cache
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .get(&key)
+            .cloned();
+        if let Some(block) = cached {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return block;
+        }
+
+        // two threads missing the same block both ask the inner world, same answer either way.
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let block = self.world.get_block(pos);
+        self.cache
+            .write()
+            .unwrap_or_else(|err| err.into_inner())
+            .insert(key, block.clone());
+        block
+    }
+
+    fn get_world_border(&self) -> Option<WorldBorder> {
+        self.world.get_world_border()
+    }
+
+    fn get_min_y(&self) -> Option<f32> {
+        self.world.get_min_y()
+    }
+}
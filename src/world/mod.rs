@@ -0,0 +1,2 @@
+pub mod border;
+pub mod caching_world;
@@ -0,0 +1,48 @@
+extern crate minecraft_physics;
+
+mod tests {
+
+    use minecraft_physics::{
+        calc::angle::{NotchianRotation, Rotation},
+        states::context_builder::EntityPhysicsContextBuilder,
+    };
+
+    #[test]
+    fn test_angle_conversions() {
+        let close = |a: glam::Vec3A, b: glam::Vec3A| a.distance(b) < 1.0e-5;
+        // protocol yaw 0 faces +z, 90 faces -x, pitch 90 looks down.
+        assert!(close(
+            NotchianRotation::new(0.0, 0.0).get_direction(),
+            glam::Vec3A::Z
+        ));
+        assert!(close(
+            NotchianRotation::new(90.0, 0.0).get_direction(),
+            -glam::Vec3A::X
+        ));
+        assert!(close(
+            NotchianRotation::new(0.0, 90.0).get_direction(),
+            -glam::Vec3A::Y
+        ));
+        // mineflayer yaw 0 faces -z.
+        assert!(close(
+            Rotation::new(0.0, 0.0).get_direction(),
+            -glam::Vec3A::Z
+        ));
+
+        let rotation = Rotation::new(0.7, -0.3);
+        let back = Rotation::from_direction(rotation.get_direction());
+        assert!((back.yaw - 0.7).abs() < 1.0e-5 && (back.pitch + 0.3).abs() < 1.0e-5);
+        let back = Rotation::from(rotation.to_notchian());
+        assert!((back.yaw - 0.7).abs() < 1.0e-5 && (back.pitch + 0.3).abs() < 1.0e-5);
+        assert_eq!(NotchianRotation::new(270.0, 10.0).wrapped().yaw, -90.0);
+
+        let ctx = EntityPhysicsContextBuilder::player()
+            .rotation(NotchianRotation::new(90.0, 0.0))
+            .build()
+            .unwrap();
+        assert!(close(
+            ctx.get_state().rotation.get_direction(),
+            -glam::Vec3A::X
+        ));
+    }
+}
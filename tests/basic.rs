@@ -1,17 +1,21 @@
 extern crate minecraft_physics;
 
+mod common;
+
 mod tests {
 
     use minecraft_physics::{
-        simulators::{self, World},
+        calc::angle::Rotation,
+        simulators::{self, Simulator, World},
         states::{
             self,
+            context_builder::EntityPhysicsContextBuilder,
             physics_context::{CollisionBehavior, EntityType},
             player_context::ControlStateHandler,
         },
     };
 
-    use super::*;
+    use super::common::FlatWorld;
 
     pub struct TestWorld {
         stone_height: u32,
@@ -86,4 +90,50 @@ mod tests {
 
         // let entity = settings::entity_physics_ctx::entity_physics_context::
     }
+
+    #[test]
+    fn test_heading_every_quadrant() {
+        let sim = simulators::prismarine_simulator::PrismarineSimulator::default();
+        for yaw in [0.3f32, 1.9, -1.2, -2.8] {
+            // facing is (-sin(yaw), -cos(yaw)), its right is (cos(yaw), -sin(yaw)).
+            let facing = glam::Vec2::new(-yaw.sin(), -yaw.cos());
+            let right = glam::Vec2::new(yaw.cos(), -yaw.sin());
+            for (strafe, heading) in [(false, facing), (true, right)] {
+                for jump in [false, true] {
+                    let mut ctx = EntityPhysicsContextBuilder::player()
+                        .position(glam::Vec3A::new(0.5, 61.0, 0.5))
+                        .on_ground(true)
+                        .rotation(Rotation::new(yaw, 0.0))
+                        .build()
+                        .unwrap();
+                    ctx.get_state_mut().control_states = ControlStateHandler {
+                        forward: !strafe,
+                        right: strafe,
+                        sprint: !strafe,
+                        jump,
+                        ..Default::default()
+                    };
+                    sim.simulate_ticks(&mut ctx, 10, &FlatWorld);
+
+                    let moved = ctx.get_state().position - glam::Vec3A::new(0.5, 61.0, 0.5);
+                    let moved = glam::Vec2::new(moved.x, moved.z);
+                    assert!(
+                        moved.length() > 0.5,
+                        "yaw {} strafe {} jump {}",
+                        yaw,
+                        strafe,
+                        jump
+                    );
+                    assert!(
+                        moved.normalize().dot(heading) > 0.999,
+                        "yaw {} strafe {} jump {}: moved {}",
+                        yaw,
+                        strafe,
+                        jump,
+                        moved
+                    );
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,81 @@
+extern crate minecraft_physics;
+
+mod tests {
+
+    use minecraft_physics::{
+        simulators::{self, gen_simulator::GenSimulator, Simulator, World},
+        states::context_builder::{ContextError, EntityPhysicsContextBuilder},
+    };
+
+    pub struct EmptyWorld;
+
+    impl World for EmptyWorld {
+        fn get_block(&self, pos: &glam::Vec3A) -> Option<simulators::Block> {
+            Some(simulators::Block::test_new(
+                "empty".to_string(),
+                0,
+                1,
+                pos.floor(),
+                vec![],
+            ))
+        }
+    }
+
+    #[test]
+    fn test_presets() {
+        let player = EntityPhysicsContextBuilder::player().build().unwrap();
+        assert!(player.is_player());
+        assert!(player.get_use_controls());
+        assert_eq!(player.get_gravity(), 0.08);
+        assert_eq!(player.get_step_height(), 0.6);
+        assert_eq!(player.get_state().height, 1.8);
+        assert_eq!(player.get_state().half_width, 0.3);
+
+        let zombie = EntityPhysicsContextBuilder::mob("zombie", 0.6, 1.95)
+            .build()
+            .unwrap();
+        assert!(zombie.is_living());
+        assert!(!zombie.get_use_controls());
+        assert_eq!(zombie.get_height(), 1.95);
+
+        let arrow = EntityPhysicsContextBuilder::projectile("arrow", 0.5, 0.5)
+            .build()
+            .unwrap();
+        assert!(!arrow.is_living());
+        assert!(!arrow.get_gravity_then_drag());
+    }
+
+    #[test]
+    fn test_validation() {
+        let err = EntityPhysicsContextBuilder::item().airdrag(1.5).build();
+        assert_eq!(
+            err.err(),
+            Some(ContextError::OutOfRange {
+                field: "airdrag",
+                value: 1.5
+            })
+        );
+        let err = EntityPhysicsContextBuilder::new("mob", "zombie").build();
+        assert_eq!(err.err(), Some(ContextError::MissingSize));
+        let err = EntityPhysicsContextBuilder::new("", "nothing").build();
+        assert_eq!(err.err(), Some(ContextError::MissingType));
+    }
+
+    #[test]
+    fn test_change_gravity_between_ticks() {
+        let sim = GenSimulator::default();
+        let mut item = EntityPhysicsContextBuilder::item()
+            .position(glam::Vec3A::new(0.5, 100.0, 0.5))
+            .build()
+            .unwrap();
+
+        sim.simulate_ticks(&mut item, 5, &EmptyWorld);
+        let y = item.get_state().position.y;
+        assert!(y < 100.0);
+
+        item.set_gravity(0.0);
+        item.get_state_mut().velocity = glam::Vec3A::ZERO;
+        sim.simulate_ticks(&mut item, 5, &EmptyWorld);
+        assert_eq!(item.get_state().position.y, y);
+    }
+}
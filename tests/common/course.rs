@@ -0,0 +1,67 @@
+//! the obstacle course the pathfinder and path follower tests run through.
+
+use minecraft_physics::{
+    blocks::shape_registry::ShapeRegistry,
+    simulators::{
+        self,
+        gen_simulator::GenSimulator,
+        pathfinder::{PathBlocks, Pathfinder},
+        World,
+    },
+};
+
+const BLOCKS: &str = r#"[
+    {"id": 0, "name": "air", "minStateId": 0, "maxStateId": 0, "states": [], "boundingBox": "empty"},
+    {"id": 1, "name": "stone", "minStateId": 1, "maxStateId": 1, "states": [], "boundingBox": "block"},
+    {"id": 2, "name": "ladder", "minStateId": 2, "maxStateId": 2, "states": [], "boundingBox": "block"}
+]"#;
+const SHAPES: &str = r#"{
+    "blocks": {"air": 0, "stone": 1, "ladder": 2},
+    "shapes": {"0": [], "1": [[0, 0, 0, 1, 1, 1]], "2": [[0, 0, 0.8125, 1, 1, 1]]}
+}"#;
+
+/// 3 wide, along +z: floor at y = 0 with a 2 block gap at z = 4..=5, a step up at z = 9,
+/// then a wall at z = 10 up to y = 5 with a ladder on it.
+pub struct Course;
+
+impl World for Course {
+    fn get_block(&self, pos: &glam::Vec3A) -> Option<simulators::Block> {
+        let pos = pos.floor();
+        let lane = (-1.0..=1.0).contains(&pos.x);
+        let floor = pos.y == 0.0 && pos.z >= 0.0 && pos.z <= 9.0 && !(4.0..=5.0).contains(&pos.z);
+        let step = pos.y == 1.0 && pos.z == 9.0;
+        let wall = (1.0..=5.0).contains(&pos.y) && pos.z == 10.0;
+        let ladder = pos.x == 0.0 && (2.0..=5.0).contains(&pos.y) && pos.z == 9.0;
+
+        let b_type = if lane && (floor || step || wall) {
+            1
+        } else if ladder {
+            2
+        } else {
+            0
+        };
+        let shapes = match b_type {
+            1 => vec![[0.0, 0.0, 0.0, 1.0, 1.0, 1.0]],
+            2 => vec![[0.0, 0.0, 0.8125, 1.0, 1.0, 1.0]],
+            _ => vec![],
+        };
+        Some(simulators::Block::test_new(
+            "block".to_string(),
+            0,
+            b_type,
+            pos,
+            shapes,
+        ))
+    }
+}
+
+pub fn registry() -> ShapeRegistry {
+    ShapeRegistry::from_json(BLOCKS, SHAPES).unwrap()
+}
+
+pub fn pathfinder(registry: &ShapeRegistry) -> Pathfinder<GenSimulator> {
+    Pathfinder::new(
+        GenSimulator::from_registry(registry),
+        PathBlocks::from_registry(registry),
+    )
+}
@@ -0,0 +1,54 @@
+//! worlds and entities shared by the integration tests.
+#![allow(dead_code)]
+
+pub mod course;
+
+use minecraft_physics::{
+    calc::angle::Rotation,
+    simulators::{self, World},
+    states::{
+        context_builder::EntityPhysicsContextBuilder, physics_context::EntityPhysicsContext,
+        player_context::ControlStateHandler,
+    },
+};
+
+/// stone up to y = 60, answers in block coordinates.
+pub struct FlatWorld;
+
+impl World for FlatWorld {
+    fn get_block(&self, pos: &glam::Vec3A) -> Option<simulators::Block> {
+        let pos = pos.floor();
+        if pos.y > 60.0 {
+            Some(simulators::Block::test_new(
+                "empty".to_string(),
+                0,
+                1,
+                pos,
+                vec![],
+            ))
+        } else {
+            Some(simulators::Block::test_new(
+                "block".to_string(),
+                0,
+                2,
+                pos,
+                vec![[0.0, 0.0, 0.0, 1.0, 1.0, 1.0]],
+            ))
+        }
+    }
+}
+
+/// a standing player at `position` facing `yaw`, holding `control_states`.
+pub fn player(
+    position: glam::Vec3A,
+    yaw: f32,
+    control_states: ControlStateHandler,
+) -> EntityPhysicsContext {
+    let mut ctx = EntityPhysicsContextBuilder::player()
+        .position(position)
+        .rotation(Rotation::new(yaw, 0.0))
+        .build()
+        .unwrap();
+    ctx.get_state_mut().control_states = control_states;
+    ctx
+}
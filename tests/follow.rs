@@ -0,0 +1,50 @@
+extern crate minecraft_physics;
+
+mod common;
+
+mod tests {
+
+    use minecraft_physics::{
+        simulators::{follow::PathFollower, gen_simulator::GenSimulator, Simulator},
+        states::context_builder::EntityPhysicsContextBuilder,
+    };
+
+    use super::common::course::{self, Course};
+
+    #[test]
+    fn test_follow_path() {
+        let registry = course::registry();
+        let start = glam::IVec3::new(0, 1, 0);
+        let goal = glam::IVec3::new(0, 6, 10);
+        let path = course::pathfinder(&registry)
+            .find(start, goal, &Course)
+            .unwrap();
+
+        let mut follower =
+            PathFollower::new(GenSimulator::from_registry(&registry), path.get_waypoints());
+        let mut ctx = EntityPhysicsContextBuilder::player()
+            .position(glam::Vec3A::new(0.5, 1.0, 0.5))
+            .on_ground(true)
+            .build()
+            .unwrap();
+        let mut jumps = 0;
+        for _ in 0..400 {
+            if follower.is_finished() {
+                break;
+            }
+            let input = follower.next_input(&ctx, &Course);
+            if input.control_states.jump && ctx.get_state().on_ground {
+                jumps += 1;
+            }
+            input.apply(&mut ctx);
+            follower.get_simulator().tick(&mut ctx, &Course);
+        }
+        assert!(follower.is_finished());
+        assert!(follower.get_target().is_none());
+        let position = ctx.get_state().position;
+        assert_eq!(position.floor().as_ivec3(), goal);
+        assert!(glam::Vec2::new(position.x - 0.5, position.z - 10.5).length() <= 0.2);
+        // over the gap and up the step.
+        assert!(jumps >= 2);
+    }
+}
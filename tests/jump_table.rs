@@ -0,0 +1,100 @@
+extern crate minecraft_physics;
+
+mod tests {
+
+    use minecraft_physics::{
+        blocks::shape_registry::ShapeRegistry,
+        simulators::{
+            gen_simulator::GenSimulator,
+            jump_table::{JumpBlocks, JumpTableConfig, JumpTableGenerator, Surface, Takeoff},
+        },
+    };
+
+    const BLOCKS: &str = r#"[
+        {"id": 0, "name": "air", "minStateId": 0, "maxStateId": 0, "states": [], "boundingBox": "empty"},
+        {"id": 1, "name": "stone", "minStateId": 1, "maxStateId": 1, "states": [], "boundingBox": "block"},
+        {"id": 2, "name": "ice", "minStateId": 2, "maxStateId": 2, "states": [], "boundingBox": "block"},
+        {"id": 3, "name": "soul_sand", "minStateId": 3, "maxStateId": 3, "states": [], "boundingBox": "block"},
+        {"id": 4, "name": "slime_block", "minStateId": 4, "maxStateId": 4, "states": [], "boundingBox": "block"}
+    ]"#;
+    const SHAPES: &str = r#"{
+        "blocks": {"air": 0, "stone": 1, "ice": 1, "soul_sand": 1, "slime_block": 1},
+        "shapes": {"0": [], "1": [[0, 0, 0, 1, 1, 1]]}
+    }"#;
+
+    #[test]
+    fn test_jump_table() {
+        let registry = ShapeRegistry::from_json(BLOCKS, SHAPES).unwrap();
+        let generator = JumpTableGenerator::new(
+            GenSimulator::from_registry(&registry),
+            JumpBlocks::from_registry(&registry).unwrap(),
+        )
+        .with_config(JumpTableConfig {
+            max_forward: 5,
+            max_side: 0,
+            min_dy: -1,
+            max_dy: 1,
+            surfaces: Surface::ALL.to_vec(),
+            ceilings: vec![None, Some(2)],
+            ..Default::default()
+        });
+        let table = generator.generate();
+
+        let furthest = |takeoff, surface, ceiling| {
+            table
+                .get_entries()
+                .iter()
+                .filter(|e| {
+                    e.takeoff == takeoff
+                        && e.surface == surface
+                        && e.ceiling == ceiling
+                        && e.offset.1 == 0
+                })
+                .map(|e| e.offset.2)
+                .max()
+                .unwrap()
+        };
+        let standing = furthest(Takeoff::Standing, Surface::Normal, None);
+        assert!(standing >= 3);
+        assert!(furthest(Takeoff::Running, Surface::Normal, None) >= standing);
+        assert!(furthest(Takeoff::Standing, Surface::SoulSand, None) <= standing);
+
+        // each surface and ceiling has to show up in the results, not just be labelled.
+        let ticks = |takeoff, surface, ceiling, offset| {
+            table.find(takeoff, surface, ceiling, offset).unwrap().ticks
+        };
+        let running = furthest(Takeoff::Running, Surface::Normal, None);
+        assert!(furthest(Takeoff::Running, Surface::SoulSand, None) < running);
+        // slime slows walking down.
+        assert!(furthest(Takeoff::Standing, Surface::Slime, None) < standing);
+        // ice barely accelerates, so the same run-up takes longer.
+        let gap = (0, 0, 4);
+        let normal = ticks(Takeoff::Running, Surface::Normal, None, gap);
+        assert!(ticks(Takeoff::Running, Surface::Ice, None, gap) > normal);
+        assert!(ticks(Takeoff::Running, Surface::SoulSand, None, gap) > normal);
+        // a head hitter cuts the jump short, so it lands sooner.
+        let gap = (0, 0, 2);
+        assert!(
+            ticks(Takeoff::Running, Surface::Normal, Some(2), gap)
+                < ticks(Takeoff::Running, Surface::Normal, None, gap)
+        );
+
+        let entry = table
+            .find(Takeoff::Standing, Surface::Normal, Some(2), (0, 0, 1))
+            .unwrap();
+        assert_eq!(entry.get_inputs().len() as u32, entry.ticks);
+        assert_eq!(entry.start, glam::Vec3A::new(0.5, 0.0, 0.5));
+
+        // running jumps start the whole run-up behind the edge block, away from the target.
+        let running = table
+            .find(Takeoff::Running, Surface::Normal, None, (0, 0, 2))
+            .unwrap();
+        let run_up = JumpTableConfig::default().run_up_blocks;
+        assert!((running.start.z - (0.5 - run_up)).abs() < 1e-4);
+        assert!((running.start.x - 0.5).abs() < 1e-4);
+
+        let csv = table.to_csv();
+        assert_eq!(csv.lines().count(), table.get_entries().len() + 1);
+        assert!(csv.starts_with("takeoff,surface,ceiling,dx,dy,dz,start_x,start_z"));
+    }
+}
@@ -0,0 +1,54 @@
+extern crate minecraft_physics;
+
+mod tests {
+
+    use std::f32::consts::PI;
+
+    use minecraft_physics::{
+        calc::angle::Rotation,
+        states::{
+            look::{get_rotation_gcd, LookController},
+            EntityState,
+        },
+    };
+
+    #[test]
+    fn test_turn_rate() {
+        let look = LookController::new().with_sensitivity(None);
+        // 3 rad/s is 0.15 per tick.
+        let step = look.step(Rotation::new(0.0, 0.0), Rotation::new(1.0, -1.0));
+        assert!((step.yaw - 0.15).abs() < 1.0e-6);
+        assert!((step.pitch + 0.15).abs() < 1.0e-6);
+
+        // the short way round, and yaw isn't wrapped.
+        let step = look.step(Rotation::new(3.0, 0.0), Rotation::new(-3.0, 0.0));
+        assert!(step.yaw > 3.0 && step.yaw < 3.0 + 0.15 + 1.0e-6);
+
+        // close targets are reached exactly.
+        let close = Rotation::new(0.1, 0.05);
+        assert_eq!(look.step(Rotation::new(0.0, 0.0), close), close);
+    }
+
+    #[test]
+    fn test_gcd_snapping() {
+        let gcd = get_rotation_gcd(0.5);
+        assert!((gcd.to_degrees() - 0.15).abs() < 1.0e-4);
+
+        let mut look = LookController::new();
+        let mut state = EntityState::default();
+        look.look_at(Rotation::new(PI / 2.0, 0.3));
+
+        let mut ticks = 0;
+        while !look.tick(&mut state) {
+            ticks += 1;
+            assert!(ticks < 100);
+            let counts = state.rotation.yaw / gcd;
+            assert!((counts - counts.round()).abs() < 1.0e-2);
+        }
+        // 0.15 rad per tick at most.
+        assert!(ticks >= 10);
+        assert!((state.rotation.yaw - PI / 2.0).abs() <= gcd);
+        assert!((state.rotation.pitch - 0.3).abs() <= gcd);
+        assert!(look.get_target().is_none());
+    }
+}
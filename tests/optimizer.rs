@@ -0,0 +1,72 @@
+extern crate minecraft_physics;
+
+mod tests {
+
+    use minecraft_physics::{
+        calc::angle::Rotation,
+        simulators::{
+            self,
+            gen_simulator::GenSimulator,
+            optimizer::{Goal, InputOptimizer},
+            Simulator, World,
+        },
+        states::context_builder::EntityPhysicsContextBuilder,
+    };
+
+    /// stone up to y = 60 for z <= 2, nothing past it.
+    pub struct LedgeWorld;
+
+    impl World for LedgeWorld {
+        fn get_block(&self, pos: &glam::Vec3A) -> Option<simulators::Block> {
+            let pos = pos.floor();
+            let shapes = if pos.y <= 60.0 && pos.z <= 2.0 {
+                vec![[0.0, 0.0, 0.0, 1.0, 1.0, 1.0]]
+            } else {
+                vec![]
+            };
+            Some(simulators::Block::test_new(
+                "block".to_string(),
+                0,
+                2,
+                pos,
+                shapes,
+            ))
+        }
+    }
+
+    #[test]
+    fn test_stop_on_edge() {
+        let sim = GenSimulator::default();
+        // facing +z, towards the edge.
+        let mut ctx = EntityPhysicsContextBuilder::player()
+            .position(glam::Vec3A::new(0.5, 61.0, 0.5))
+            .on_ground(true)
+            .rotation(Rotation::new(std::f32::consts::PI, 0.0))
+            .build()
+            .unwrap();
+        ctx.get_state_mut().velocity.y = -0.0784;
+
+        // as far out as the player's box allows, stopped, still on the ledge.
+        let edge = glam::Vec3A::new(0.5, 61.0, 3.29);
+        let goal = Goal::position(edge)
+            .with_velocity(glam::Vec3A::new(0.0, -0.0784, 0.0))
+            .with_on_ground(true);
+        let optimizer = InputOptimizer::new(GenSimulator::default()).with_beam_width(16);
+        let result = optimizer.optimize(&mut ctx, goal, 16, &LedgeWorld);
+
+        assert_eq!(result.inputs.len(), 16);
+        assert!(result.error < 0.1, "error {}", result.error);
+        assert!(result.state.on_ground);
+        assert!(result.state.position.z > 3.0);
+        // ctx is untouched.
+        assert_eq!(ctx.get_state().position, glam::Vec3A::new(0.5, 61.0, 0.5));
+
+        // the inputs replay to the reported state.
+        for input in result.inputs.iter() {
+            input.apply(&mut ctx);
+            sim.tick(&mut ctx, &LedgeWorld);
+        }
+        assert_eq!(ctx.get_state().position, result.state.position);
+        assert_eq!(goal.get_error(ctx.get_state()), result.error);
+    }
+}
@@ -0,0 +1,67 @@
+extern crate minecraft_physics;
+
+mod common;
+
+mod tests {
+
+    use minecraft_physics::{
+        simulators::{pathfinder::MoveKind, Simulator, World},
+        states::context_builder::EntityPhysicsContextBuilder,
+    };
+
+    use super::common::course::{self, Course};
+
+    #[test]
+    fn test_pathfinder() {
+        let pathfinder = course::pathfinder(&course::registry());
+        let start = glam::IVec3::new(0, 1, 0);
+        let goal = glam::IVec3::new(0, 6, 10);
+        let path = pathfinder.find(start, goal, &Course).unwrap();
+
+        let waypoints = path.get_waypoints();
+        assert_eq!(waypoints.first(), Some(&start));
+        assert_eq!(waypoints.last(), Some(&goal));
+        let kinds: Vec<_> = path.segments.iter().map(|s| s.kind).collect();
+        for kind in [MoveKind::SprintJump, MoveKind::Ascend, MoveKind::Climb] {
+            assert!(kinds.contains(&kind), "{:?} in {:?}", kind, kinds);
+        }
+        let jump = path
+            .segments
+            .iter()
+            .find(|s| s.kind == MoveKind::SprintJump)
+            .unwrap();
+        assert!(jump.get_control_states().sprint);
+        assert!(jump.get_jump_tick().is_some());
+
+        // every segment starting on the ground replays to its end block.
+        let on_ladder =
+            |pos: glam::IVec3| Course.get_block(&pos.as_vec3a()).unwrap().get_type() == 2;
+        for segment in path.segments.iter().filter(|s| !on_ladder(s.from)) {
+            let mut ctx = EntityPhysicsContextBuilder::player()
+                .position(segment.from.as_vec3a() + glam::Vec3A::new(0.5, 0.0, 0.5))
+                .on_ground(true)
+                .build()
+                .unwrap();
+            ctx.get_state_mut().velocity.y = -0.0784;
+            for input in segment.inputs.iter() {
+                input.apply(&mut ctx);
+                pathfinder.get_simulator().tick(&mut ctx, &Course);
+            }
+            assert_eq!(ctx.get_state().position.floor().as_ivec3(), segment.to);
+        }
+        assert_eq!(
+            path.get_ticks(),
+            path.segments.iter().map(|s| s.get_ticks()).sum::<u32>()
+        );
+    }
+
+    #[test]
+    fn test_no_path() {
+        let pathfinder = course::pathfinder(&course::registry()).with_max_nodes(200);
+        // past the end of the course, over nothing.
+        let goal = glam::IVec3::new(0, 1, 20);
+        assert!(pathfinder
+            .find(glam::IVec3::new(0, 1, 0), goal, &Course)
+            .is_none());
+    }
+}
@@ -0,0 +1,77 @@
+extern crate minecraft_physics;
+
+mod tests {
+
+    use minecraft_physics::{
+        blocks::properties::Direction,
+        calc::angle::Rotation,
+        simulators::{self, raycast::raycast, World},
+    };
+
+    /// stone up to y = 60, a wall at z = 3, and a bottom slab at 0 61 1.
+    pub struct RoomWorld;
+
+    impl World for RoomWorld {
+        fn get_block(&self, pos: &glam::Vec3A) -> Option<simulators::Block> {
+            let pos = pos.floor();
+            let shapes = if pos.y <= 60.0 || pos.z == 3.0 {
+                vec![[0.0, 0.0, 0.0, 1.0, 1.0, 1.0]]
+            } else if pos == glam::Vec3A::new(0.0, 61.0, 1.0) {
+                vec![[0.0, 0.0, 0.0, 1.0, 0.5, 1.0]]
+            } else {
+                vec![]
+            };
+            Some(simulators::Block::test_new(
+                "block".to_string(),
+                0,
+                2,
+                pos,
+                shapes,
+            ))
+        }
+    }
+
+    #[test]
+    fn test_raycast() {
+        let eye = glam::Vec3A::new(0.5, 62.62, 0.5);
+
+        let down = Rotation::new(0.0, -std::f32::consts::FRAC_PI_2).get_direction();
+        let hit = raycast(&RoomWorld, eye, down, 5.0).unwrap();
+        assert_eq!(hit.block.get_position(), glam::Vec3A::new(0.0, 60.0, 0.0));
+        assert_eq!(hit.face, Direction::Up);
+        assert!((hit.distance - 1.62).abs() < 1.0e-4);
+        assert!((hit.position.y - 61.0).abs() < 1.0e-4);
+
+        // facing +z, over the slab and into the wall.
+        let hit = raycast(&RoomWorld, eye, glam::Vec3A::Z, 5.0).unwrap();
+        assert_eq!(hit.block.get_position(), glam::Vec3A::new(0.0, 62.0, 3.0));
+        assert_eq!(hit.face, Direction::North);
+        assert!((hit.position.z - 3.0).abs() < 1.0e-5);
+        assert!((hit.distance - 2.5).abs() < 1.0e-5);
+
+        // down onto the slab's top, not its block's.
+        let towards = glam::Vec3A::new(0.5, 61.0, 1.5) - eye;
+        let hit = raycast(&RoomWorld, eye, towards, 5.0).unwrap();
+        assert_eq!(hit.block.get_position(), glam::Vec3A::new(0.0, 61.0, 1.0));
+        assert_eq!(hit.face, Direction::Up);
+        assert!((hit.position.y - 61.5).abs() < 1.0e-4);
+        assert!(!hit.inside);
+
+        // out of reach.
+        assert!(raycast(&RoomWorld, eye, glam::Vec3A::Z, 2.0).is_none());
+        assert!(raycast(&RoomWorld, eye, glam::Vec3A::Y, 100.0).is_none());
+
+        // starting inside the wall hits it right away.
+        let buried = glam::Vec3A::new(0.5, 62.5, 3.5);
+        let hit = raycast(&RoomWorld, buried, glam::Vec3A::Z, 5.0).unwrap();
+        assert!(hit.inside);
+        assert_eq!(hit.block.get_position(), glam::Vec3A::new(0.0, 62.0, 3.0));
+        assert_eq!(hit.face, Direction::North);
+        assert_eq!(hit.distance, 0.0);
+        assert_eq!(hit.position, buried);
+
+        // an endless ray is rejected rather than walked forever.
+        assert!(raycast(&RoomWorld, eye, glam::Vec3A::Y, f32::INFINITY).is_none());
+        assert!(raycast(&RoomWorld, eye, glam::Vec3A::Y, f32::NAN).is_none());
+    }
+}
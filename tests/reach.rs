@@ -0,0 +1,95 @@
+extern crate minecraft_physics;
+
+mod tests {
+
+    use minecraft_physics::{
+        simulators::{
+            self,
+            prismarine_simulator::PrismarineSimulator,
+            reach::{Approach, ReachabilitySearch},
+            Simulator, World,
+        },
+        states::context_builder::EntityPhysicsContextBuilder,
+    };
+
+    /// floor at y = 60 for z <= 0, then past a 2 block gap z = 3..=4, then past a 4 block gap
+    /// z >= 9. plus a raised block at x = 3 z = 0.
+    pub struct GapWorld;
+
+    impl World for GapWorld {
+        fn get_block(&self, pos: &glam::Vec3A) -> Option<simulators::Block> {
+            let pos = pos.floor();
+            let floor = pos.z <= 0.0 || (3.0..=4.0).contains(&pos.z) || pos.z >= 9.0;
+            let solid = (pos.y <= 60.0 && floor) || (pos.y == 61.0 && pos.x == 3.0 && pos.z == 0.0);
+            let shapes = if solid {
+                vec![[0.0, 0.0, 0.0, 1.0, 1.0, 1.0]]
+            } else {
+                vec![]
+            };
+            Some(simulators::Block::test_new(
+                "block".to_string(),
+                0,
+                2,
+                pos,
+                shapes,
+            ))
+        }
+    }
+
+    #[test]
+    fn test_reach_gap() {
+        let search = ReachabilitySearch::default();
+        let start = glam::Vec3A::new(0.0, 61.0, 0.0);
+        let target = glam::Vec3A::new(0.0, 61.0, 3.0);
+
+        let standing = search
+            .find(start, target, Approach::Standing, &GapWorld)
+            .unwrap();
+        assert_eq!(standing.landing.floor(), target);
+        assert!(standing.inputs.iter().any(|i| i.control_states.jump));
+
+        // the plan replays to the same place.
+        let sim = PrismarineSimulator::default();
+        let mut ctx = EntityPhysicsContextBuilder::player()
+            .position(glam::Vec3A::new(0.5, 61.0, 0.5))
+            .velocity(glam::Vec3A::new(0.0, -0.0784, 0.0))
+            .on_ground(true)
+            .build()
+            .unwrap();
+        for input in standing.inputs.iter() {
+            input.apply(&mut ctx);
+            sim.tick(&mut ctx, &GapWorld);
+        }
+        assert_eq!(ctx.get_state().position, standing.landing);
+
+        let sprinting = search
+            .find(start, target, Approach::Sprinting, &GapWorld)
+            .unwrap();
+        assert!(sprinting.get_ticks() <= standing.get_ticks());
+    }
+
+    #[test]
+    fn test_reach_up_and_impossible() {
+        let search = ReachabilitySearch::default();
+        let start = glam::Vec3A::new(2.0, 61.0, 0.0);
+
+        let up = search
+            .find(
+                start,
+                glam::Vec3A::new(3.0, 62.0, 0.0),
+                Approach::Standing,
+                &GapWorld,
+            )
+            .unwrap();
+        assert!(up.inputs.iter().any(|i| i.control_states.jump));
+
+        // 4 blocks of gap.
+        let far = search.find(
+            glam::Vec3A::new(0.0, 61.0, 4.0),
+            glam::Vec3A::new(0.0, 61.0, 9.0),
+            Approach::Standing,
+            &GapWorld,
+        );
+        assert!(far.is_none());
+    }
+}
@@ -0,0 +1,97 @@
+extern crate minecraft_physics;
+
+mod common;
+
+mod tests {
+
+    use minecraft_physics::{
+        blocks::shape_registry::{RegistryWorld, ShapeRegistry, StateWorld},
+        calc::aabb::AABB,
+        simulators::{prismarine_simulator::PrismarineSimulator, Simulator, World},
+        states::player_context::ControlStateHandler,
+    };
+
+    use super::common;
+
+    fn load_registry() -> ShapeRegistry {
+        ShapeRegistry::from_json(
+            include_str!("data/blocks.json"),
+            include_str!("data/blockCollisionShapes.json"),
+        )
+        .unwrap()
+    }
+
+    /// flat floor of a single state at y = 60.
+    pub struct FloorWorld {
+        floor_state: u32,
+    }
+
+    impl StateWorld for FloorWorld {
+        fn get_block_state(&self, pos: &glam::Vec3A) -> Option<u32> {
+            if pos.y.floor() == 60.0 {
+                Some(self.floor_state)
+            } else {
+                Some(0)
+            }
+        }
+    }
+
+    #[test]
+    fn test_resolve_states() {
+        let registry = load_registry();
+
+        assert_eq!(registry.get_block_id("oak_slab"), Some(2));
+        // type=top
+        assert_eq!(registry.get_shapes(2), &[[0.0, 0.5, 0.0, 1.0, 1.0, 1.0]]);
+        // type=bottom
+        assert_eq!(registry.get_shapes(4), &[[0.0, 0.0, 0.0, 1.0, 0.5, 1.0]]);
+        assert_eq!(registry.get_shapes(24)[0][4], 1.5);
+        assert!(registry.get_shapes(8).is_empty());
+
+        // type=bottom, waterlogged=true
+        assert!(registry.get_properties(4).waterlogged);
+        assert!(!registry.get_properties(5).waterlogged);
+        assert_eq!(registry.get_properties(8 + 3).level, Some(3));
+
+        let world = RegistryWorld::new(&registry, FloorWorld { floor_state: 4 });
+        let block = world
+            .get_block(&glam::Vec3A::new(3.5, 60.2, -1.5))
+            .unwrap();
+        assert_eq!(block.get_position(), glam::Vec3A::new(3.0, 60.0, -2.0));
+        assert!(block.get_properties().waterlogged);
+    }
+
+    #[test]
+    fn test_bamboo_offset() {
+        let registry = load_registry();
+        let world = RegistryWorld::new(&registry, FloorWorld { floor_state: 25 });
+
+        // block (0, 60, 0) hashes to the most negative offset on both axes.
+        let bbs = PrismarineSimulator::get_surrounding_block_bbs(
+            &AABB::new(0.1, 61.0, 0.1, 0.9, 61.5, 0.9),
+            &world,
+        );
+        assert_eq!(bbs.len(), 1);
+        assert_eq!(bbs[0].min_x, 0.40625 - 0.25);
+        assert_eq!(bbs[0].min_z, 0.40625 - 0.25);
+        assert_eq!(bbs[0].min_y, 60.0);
+    }
+
+    #[test]
+    fn test_land_on_slab() {
+        let registry = load_registry();
+        let world = RegistryWorld::new(&registry, FloorWorld { floor_state: 5 });
+        let sim = PrismarineSimulator::from_registry(&registry);
+
+        let mut ctx = common::player(
+            glam::Vec3A::new(0.5, 62.0, 0.5),
+            0.0,
+            ControlStateHandler::default(),
+        );
+        for _ in 0..40 {
+            ctx = sim.simulate(ctx, &world);
+        }
+        assert!(ctx.get_state().on_ground);
+        assert_eq!(ctx.get_state().position.y, 60.5);
+    }
+}
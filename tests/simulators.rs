@@ -0,0 +1,457 @@
+extern crate minecraft_physics;
+
+mod common;
+
+mod tests {
+
+    use minecraft_physics::{
+        simulators::{self, gen_simulator::GenSimulator, Simulator, World},
+        states::{physics_context::EntityPhysicsContext, player_context::ControlStateHandler},
+    };
+
+    use super::common;
+
+    /// a 3x3 stone platform at y = 60, around 0 0.
+    pub struct PlatformWorld;
+
+    impl World for PlatformWorld {
+        fn get_block(&self, pos: &glam::Vec3A) -> Option<simulators::Block> {
+            let pos = pos.floor();
+            if pos.y == 60.0 && pos.x.abs() <= 1.0 && pos.z.abs() <= 1.0 {
+                Some(simulators::Block::test_new(
+                    "block".to_string(),
+                    0,
+                    2,
+                    pos,
+                    vec![[0.0, 0.0, 0.0, 1.0, 1.0, 1.0]],
+                ))
+            } else {
+                Some(simulators::Block::test_new(
+                    "empty".to_string(),
+                    0,
+                    1,
+                    pos,
+                    vec![],
+                ))
+            }
+        }
+    }
+
+    /// a player standing in the middle of the platform.
+    fn player(yaw: f32, control_states: ControlStateHandler) -> EntityPhysicsContext {
+        let mut ctx = common::player(glam::Vec3A::new(0.5, 61.0, 0.5), yaw, control_states);
+        ctx.get_state_mut().on_ground = true;
+        ctx
+    }
+
+    #[test]
+    fn test_gen_walk_off_edge() {
+        let sim = GenSimulator::default();
+        let mut ctx = player(
+            0.0,
+            ControlStateHandler {
+                forward: true,
+                sprint: true,
+                ..Default::default()
+            },
+        );
+
+        // yaw 0 faces -z, off the platform at z = -1.
+        for _ in 0..40 {
+            ctx = sim.simulate(ctx, &PlatformWorld);
+        }
+        assert!(ctx.get_state().position.z < -1.0);
+        assert!(ctx.get_state().position.y < 61.0);
+        assert!(!ctx.get_state().on_ground);
+    }
+
+    #[test]
+    fn test_gen_strafe_direction() {
+        let sim = GenSimulator::default();
+        let strafe = |yaw: f32, right: bool| {
+            let mut ctx = player(
+                yaw,
+                ControlStateHandler {
+                    left: !right,
+                    right,
+                    ..Default::default()
+                },
+            );
+            let start = ctx.get_state().position;
+            for _ in 0..5 {
+                ctx = sim.simulate(ctx, &PlatformWorld);
+            }
+            ctx.get_state().position - start
+        };
+
+        // facing -z, right is +x.
+        let moved = strafe(0.0, true);
+        assert!(moved.x > 0.3 && moved.z.abs() < 1.0e-4);
+        assert!(strafe(0.0, false).x < -0.3);
+        // facing +x, right is +z.
+        let moved = strafe(-std::f32::consts::FRAC_PI_2, true);
+        assert!(moved.z > 0.3 && moved.x.abs() < 1.0e-4);
+        assert!(strafe(-std::f32::consts::FRAC_PI_2, false).z < -0.3);
+    }
+
+    #[test]
+    fn test_gen_sneak_stops_at_edge() {
+        let sim = GenSimulator::default();
+        let mut ctx = player(
+            0.0,
+            ControlStateHandler {
+                forward: true,
+                sneak: true,
+                ..Default::default()
+            },
+        );
+
+        for _ in 0..100 {
+            ctx = sim.simulate(ctx, &PlatformWorld);
+        }
+        assert!(ctx.get_state().on_ground);
+        assert_eq!(ctx.get_state().position.y, 61.0);
+        // hanging over the edge by at most the player's half width.
+        assert!(ctx.get_state().position.z >= -1.0 - 0.3);
+        assert!(ctx.get_state().position.z < -0.9);
+    }
+
+    #[test]
+    fn test_simulate_until_landed() {
+        let sim = GenSimulator::default();
+        let mut ctx = player(
+            0.0,
+            ControlStateHandler {
+                jump: true,
+                ..Default::default()
+            },
+        );
+
+        let summaries = sim.simulate_ticks(&mut ctx, 1, &PlatformWorld);
+        assert!(summaries[0].left_ground);
+
+        // a standing jump lands on the 12th tick, the first one already ran.
+        let ticks = sim.simulate_until(&mut ctx, 40, &PlatformWorld, |_, summary| summary.landed);
+        assert_eq!(ticks, Some(11));
+        assert_eq!(ctx.get_state().position.y, 61.0);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_batch_matches_sequential() {
+        use minecraft_physics::simulators::batch::BatchSimulator;
+
+        let controls = ControlStateHandler {
+            forward: true,
+            sprint: true,
+            jump: true,
+            ..Default::default()
+        };
+        let make = || -> Vec<EntityPhysicsContext> {
+            (0..64).map(|i| player(i as f32 * 0.1, controls)).collect()
+        };
+
+        let batch = BatchSimulator::new(GenSimulator::default(), 4).unwrap();
+        let mut batched = make();
+        batch.simulate_ticks_all(&mut batched, 30, &PlatformWorld);
+
+        let sim = GenSimulator::default();
+        let mut sequential = make();
+        for ctx in sequential.iter_mut() {
+            sim.simulate_ticks(ctx, 30, &PlatformWorld);
+        }
+
+        for (a, b) in batched.iter().zip(sequential.iter()) {
+            assert_eq!(a.get_state().position, b.get_state().position);
+            assert_eq!(a.get_state().velocity, b.get_state().velocity);
+        }
+    }
+
+    #[test]
+    fn test_lanes_match_sequential() {
+        use minecraft_physics::simulators::lanes::LaneSimulator;
+
+        // walkers in every direction, some jumping, some sneaking (scalar path).
+        let make = || -> Vec<EntityPhysicsContext> {
+            (0..30)
+                .map(|i| {
+                    player(
+                        i as f32 * 0.7,
+                        ControlStateHandler {
+                            forward: i % 5 != 0,
+                            sprint: i % 2 == 0,
+                            jump: i % 3 == 0,
+                            sneak: i % 7 == 0,
+                            ..Default::default()
+                        },
+                    )
+                })
+                .collect()
+        };
+
+        let mut lanes = LaneSimulator::new(GenSimulator::default());
+        let mut laned = make();
+        lanes.simulate_ticks_all(&mut laned, 40, &PlatformWorld);
+
+        let sim = GenSimulator::default();
+        let mut sequential = make();
+        for ctx in sequential.iter_mut() {
+            sim.simulate_ticks(ctx, 40, &PlatformWorld);
+        }
+
+        for (a, b) in laned.iter().zip(sequential.iter()) {
+            assert_eq!(a.get_state().position, b.get_state().position);
+            assert_eq!(a.get_state().velocity, b.get_state().velocity);
+            assert_eq!(a.get_state().on_ground, b.get_state().on_ground);
+        }
+
+        let stats = lanes.get_stats();
+        assert!(stats.simd > stats.scalar);
+        assert!(stats.scalar > 0);
+    }
+
+    #[test]
+    fn test_trajectory_round_trip_and_replay() {
+        use minecraft_physics::{
+            calc::angle::Rotation,
+            simulators::trajectory::{Trajectory, TrajectoryRecorder},
+            states::player_context::TickInput,
+        };
+
+        let sim = GenSimulator::default();
+        let mut ctx = player(0.0, ControlStateHandler::default());
+        let mut recorder = TrajectoryRecorder::new(&ctx);
+        for i in 0..30 {
+            let input = TickInput::new(
+                ControlStateHandler {
+                    forward: true,
+                    jump: i % 10 == 5,
+                    ..Default::default()
+                },
+                Rotation::new(i as f32 * 0.2, 0.0),
+            );
+            recorder.tick_with(&sim, &mut ctx, input, &PlatformWorld);
+        }
+        let trajectory = recorder.into_trajectory();
+
+        let text = trajectory.to_json_lines();
+        // the starting state, then a line per tick.
+        assert_eq!(text.lines().count(), 31);
+        let parsed = Trajectory::from_json_lines(&text).unwrap();
+        assert_eq!(parsed, trajectory);
+        assert_eq!(
+            parsed.get_start().position,
+            glam::Vec3A::new(0.5, 61.0, 0.5)
+        );
+        assert!(parsed.get_start().on_ground);
+
+        // replays from the recorded start, wherever the context was.
+        let mut replayed = common::player(
+            glam::Vec3A::new(20.0, 80.0, 20.0),
+            2.0,
+            ControlStateHandler::default(),
+        );
+        assert_eq!(
+            parsed.replay(&sim, &mut replayed, &PlatformWorld, 1e-6),
+            None
+        );
+        assert_eq!(replayed.get_state().position, ctx.get_state().position);
+
+        // without the platform the player falls, the first tick starts at rest
+        // so it only shows on the second one.
+        struct Void;
+        impl World for Void {
+            fn get_block(&self, _pos: &glam::Vec3A) -> Option<simulators::Block> {
+                None
+            }
+        }
+        let mut replayed = player(0.0, ControlStateHandler::default());
+        let divergence = parsed.replay(&sim, &mut replayed, &Void, 1e-6).unwrap();
+        assert_eq!(divergence.tick, 1);
+        assert!(!divergence.actual.on_ground);
+        assert!(divergence.get_position_error() > 0.0);
+    }
+
+    /// floor at y = 60, a slab across z = -3 and a wall at z = -6.
+    pub struct StepWorld;
+
+    impl World for StepWorld {
+        fn get_block(&self, pos: &glam::Vec3A) -> Option<simulators::Block> {
+            let pos = pos.floor();
+            let shapes = if pos.y <= 60.0 || (pos.y <= 62.0 && pos.z == -6.0) {
+                vec![[0.0, 0.0, 0.0, 1.0, 1.0, 1.0]]
+            } else if pos.y == 61.0 && pos.z == -3.0 {
+                vec![[0.0, 0.0, 0.0, 1.0, 0.5, 1.0]]
+            } else {
+                vec![]
+            };
+            Some(simulators::Block::test_new(
+                "block".to_string(),
+                0,
+                2,
+                pos,
+                shapes,
+            ))
+        }
+    }
+
+    #[test]
+    fn test_tick_events() {
+        use minecraft_physics::simulators::events::PhysicsEvent;
+
+        let sim = GenSimulator::default();
+        let mut ctx = player(
+            0.0,
+            ControlStateHandler {
+                jump: true,
+                ..Default::default()
+            },
+        );
+        let mut events = vec![];
+        for _ in 0..12 {
+            events.extend(sim.tick_with_events(&mut ctx, &PlatformWorld));
+        }
+        let landings: Vec<_> = events
+            .iter()
+            .filter_map(|e| match e {
+                PhysicsEvent::Landed { fall_distance } => Some(*fall_distance),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(landings.len(), 1);
+        // vanilla doesn't count the landing tick's own movement, so less than the 1.2522 peak.
+        assert!(landings[0] > 1.1 && landings[0] < 1.2522);
+
+        // walk -z over the slab and into the wall.
+        let mut ctx = player(
+            0.0,
+            ControlStateHandler {
+                forward: true,
+                ..Default::default()
+            },
+        );
+        let mut events = vec![];
+        for _ in 0..60 {
+            sim.tick_with_listener(
+                &mut ctx,
+                &StepWorld,
+                &mut |_: &EntityPhysicsContext, e: &PhysicsEvent| events.push(*e),
+            );
+        }
+        assert!(events.iter().any(|e| match e {
+            PhysicsEvent::SteppedUp { height } => (height - 0.5).abs() < 1.0e-4,
+            _ => false,
+        }));
+        assert_eq!(
+            events
+                .iter()
+                .filter(|e| **e == PhysicsEvent::HitWall)
+                .count(),
+            1
+        );
+        assert!(ctx.get_state().is_collided_horizontally);
+    }
+
+    #[test]
+    fn test_tick_trace() {
+        use minecraft_physics::simulators::trace::TraceStage;
+
+        let sim = GenSimulator::default();
+        let mut ctx = player(
+            0.0,
+            ControlStateHandler {
+                forward: true,
+                ..Default::default()
+            },
+        );
+        // run into the wall, then look at the tick that got blocked.
+        sim.simulate_ticks(&mut ctx, 60, &StepWorld);
+        let trace = sim.tick_with_trace(&mut ctx, &StepWorld);
+
+        let stages = trace.get_stages();
+        assert!(matches!(stages.first(), Some(TraceStage::Start { .. })));
+        assert!(matches!(stages.last(), Some(TraceStage::End { .. })));
+        assert!(trace
+            .find(|s| matches!(s, TraceStage::Friction { .. }))
+            .is_some());
+        match trace.find(|s| matches!(s, TraceStage::Collision { .. })) {
+            Some(TraceStage::Collision { requested, allowed }) => {
+                assert!(requested.z < 0.0);
+                assert_eq!(allowed.z, 0.0);
+            }
+            _ => panic!("no collision stage"),
+        }
+        assert_eq!(trace.to_string().lines().count(), stages.len());
+
+        // nothing is recorded outside of tick_with_trace.
+        sim.tick(&mut ctx, &StepWorld);
+        assert_eq!(
+            sim.tick_with_trace(&mut ctx, &StepWorld).stages.len(),
+            stages.len()
+        );
+    }
+
+    #[test]
+    fn test_reconcile_correction() {
+        use minecraft_physics::{
+            calc::angle::Rotation,
+            simulators::reconcile::{ReconcileError, Reconciler},
+            states::player_context::TickInput,
+        };
+
+        let sim = GenSimulator::default();
+        let input = |i: u64| {
+            TickInput::new(
+                ControlStateHandler {
+                    forward: true,
+                    jump: i % 7 == 3,
+                    ..Default::default()
+                },
+                Rotation::new(std::f32::consts::PI + i as f32 * 0.05, 0.0),
+            )
+        };
+
+        let mut ctx = player(0.0, ControlStateHandler::default());
+        let mut reconciler = Reconciler::new(16);
+        for i in 0..20 {
+            assert_eq!(reconciler.predict(&sim, &mut ctx, input(i), &StepWorld), i);
+        }
+        assert_eq!(reconciler.len(), 16);
+        assert_eq!(
+            reconciler
+                .reconcile(&sim, &mut ctx, 2, Default::default(), &StepWorld)
+                .err(),
+            Some(ReconcileError::TooOld { tick: 2, oldest: 4 })
+        );
+        assert_eq!(
+            reconciler
+                .reconcile(&sim, &mut ctx, 20, Default::default(), &StepWorld)
+                .err(),
+            Some(ReconcileError::NotPredicted(20))
+        );
+
+        // the server had us a block further along x at the end of tick 10.
+        let mut authoritative = reconciler.get_state(10).unwrap().clone();
+        authoritative.position.x += 1.0;
+
+        let mut expected = player(0.0, ControlStateHandler::default());
+        *expected.get_state_mut() = authoritative.clone();
+        for i in 11..20 {
+            input(i).apply(&mut expected);
+            sim.tick(&mut expected, &StepWorld);
+        }
+
+        let corrected = reconciler
+            .reconcile(&sim, &mut ctx, 10, authoritative, &StepWorld)
+            .unwrap();
+        assert_eq!(corrected.position, expected.get_state().position);
+        assert_eq!(ctx.get_state().position, expected.get_state().position);
+        assert_eq!(
+            reconciler.get_state(19).unwrap().velocity,
+            expected.get_state().velocity
+        );
+        // everything before the correction is gone.
+        assert_eq!(reconciler.len(), 10);
+        assert!(reconciler.get_state(9).is_none());
+    }
+}
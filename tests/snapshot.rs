@@ -0,0 +1,88 @@
+#![cfg(feature = "serde")]
+extern crate minecraft_physics;
+
+mod common;
+
+mod tests {
+
+    use minecraft_physics::{
+        simulators::{gen_simulator::GenSimulator, Simulator},
+        states::{
+            physics_context::EntityPhysicsContext,
+            player_context::ControlStateHandler,
+            snapshot::{Snapshot, SnapshotError, SCHEMA_VERSION},
+        },
+    };
+
+    use super::common::{self, FlatWorld};
+
+    fn walker() -> EntityPhysicsContext {
+        common::player(
+            glam::Vec3A::new(0.5, 61.0, 0.5),
+            1.0,
+            ControlStateHandler {
+                forward: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let sim = GenSimulator::default();
+        let mut ctx = walker();
+        sim.simulate_ticks(&mut ctx, 10, &FlatWorld);
+
+        let mut snapshot = Snapshot::new(vec![ctx]);
+        let json = snapshot.to_json().unwrap();
+        let mut original = snapshot.contexts.remove(0);
+        let mut restored = Snapshot::from_json(&json).unwrap();
+        assert_eq!(restored.version, SCHEMA_VERSION);
+        let mut restored = restored.contexts.remove(0);
+
+        // the entity type and its physics constants survive the round trip.
+        assert_eq!(restored.get_entity_type(), original.get_entity_type());
+        assert_eq!(restored.get_gravity(), original.get_gravity());
+        assert_eq!(restored.get_airdrag(), original.get_airdrag());
+        assert_eq!(restored.get_width(), original.get_width());
+        assert_eq!(restored.get_height(), original.get_height());
+        assert_eq!(restored.get_use_controls(), original.get_use_controls());
+        assert!(restored.get_gravity() > 0.0);
+        assert!(restored.get_width() > 0.0);
+
+        // continuing from the snapshot matches continuing the original.
+        let start = original.get_state().position;
+        for _ in 0..10 {
+            sim.tick(&mut original, &FlatWorld);
+            sim.tick(&mut restored, &FlatWorld);
+            assert_eq!(original.get_state().position, restored.get_state().position);
+            assert_eq!(original.get_state().velocity, restored.get_state().velocity);
+        }
+        assert!(original.get_state().position.distance(start) > 1.0);
+        assert!(restored.get_state().control_states.forward);
+    }
+
+    #[test]
+    fn test_snapshot_version_mismatch() {
+        let json = r#"{"version": 999, "contexts": []}"#;
+        assert!(matches!(
+            Snapshot::from_json(json),
+            Err(SnapshotError::UnsupportedVersion(999))
+        ));
+    }
+
+    #[test]
+    fn test_snapshot_missing_physics_field() {
+        let json = Snapshot::new(vec![walker()]).to_json().unwrap();
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["contexts"][0]
+            .as_object_mut()
+            .unwrap()
+            .remove("gravity")
+            .unwrap();
+        assert!(matches!(
+            Snapshot::from_json(&value.to_string()),
+            Err(SnapshotError::Json(_))
+        ));
+    }
+}
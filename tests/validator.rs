@@ -0,0 +1,121 @@
+extern crate minecraft_physics;
+
+mod common;
+
+mod tests {
+
+    use minecraft_physics::{
+        calc::angle::Rotation,
+        simulators::{
+            gen_simulator::GenSimulator,
+            inputs::InputCandidate,
+            validator::{MovementValidator, Violation},
+            Simulator,
+        },
+        states::{context_builder::EntityPhysicsContextBuilder, EntityState},
+    };
+
+    use super::common::FlatWorld;
+
+    /// state after running `ticks` ticks of sprinting forward, and the state after one more.
+    fn sprinting(ticks: u32, y: f32) -> (EntityState, EntityState) {
+        let sim = GenSimulator::default();
+        let mut ctx = EntityPhysicsContextBuilder::player()
+            .position(glam::Vec3A::new(0.5, y, 0.5))
+            .rotation(Rotation::new(0.7, 0.0))
+            .build()
+            .unwrap();
+        let sprint = InputCandidate {
+            control_states: minecraft_physics::states::player_context::ControlStateHandler {
+                forward: true,
+                sprint: true,
+                ..Default::default()
+            },
+            using_item: false,
+        };
+        sprint.apply(&mut ctx);
+        sim.simulate_ticks(&mut ctx, ticks, &FlatWorld);
+        let previous = ctx.get_state().clone();
+        sim.tick(&mut ctx, &FlatWorld);
+        (previous, ctx.get_state().clone())
+    }
+
+    #[test]
+    fn test_candidates() {
+        let all: Vec<_> = InputCandidate::all().collect();
+        assert_eq!(all.len(), 78);
+        assert!(all
+            .iter()
+            .all(|c| !c.control_states.sprint || c.control_states.forward));
+    }
+
+    #[test]
+    fn test_legit_move() {
+        let validator = MovementValidator::new(GenSimulator::default());
+        let (previous, next) = sprinting(10, 61.0);
+        let verdict = validator.check(&previous, next.position, next.on_ground, &FlatWorld);
+        assert!(verdict.is_valid());
+        assert!(verdict.offset.length() < 1.0e-5);
+        assert!(verdict.input.control_states.sprint);
+    }
+
+    #[test]
+    fn test_violations() {
+        let validator = MovementValidator::new(GenSimulator::default());
+        let (previous, next) = sprinting(10, 61.0);
+
+        let fast = next.position + glam::Vec3A::new(0.4, 0.0, 0.0);
+        let verdict = validator.check(&previous, fast, true, &FlatWorld);
+        assert!(matches!(verdict.violation, Some(Violation::Speed { .. })));
+
+        let up = next.position + glam::Vec3A::new(0.0, 0.8, 0.0);
+        let verdict = validator.check(&previous, up, false, &FlatWorld);
+        assert!(matches!(
+            verdict.violation,
+            Some(Violation::Fly { vertical }) if vertical > 0.0
+        ));
+
+        // falling from high up, but claiming to be on ground.
+        let (previous, next) = sprinting(10, 80.0);
+        let verdict = validator.check(&previous, next.position, true, &FlatWorld);
+        match verdict.violation {
+            Some(Violation::NoFall { fall_distance }) => assert!(fall_distance > 1.0),
+            _ => panic!("expected no-fall"),
+        }
+    }
+
+    #[test]
+    fn test_infer_inputs() {
+        use minecraft_physics::simulators::infer::{InputInference, Observation};
+
+        let (previous, next) = sprinting(10, 61.0);
+        let mut ctx = EntityPhysicsContextBuilder::player()
+            .state(previous.clone())
+            .build()
+            .unwrap();
+        // we don't know where it was looking.
+        ctx.get_state_mut().rotation = Rotation::new(0.0, 0.0);
+
+        let inference = InputInference::new(GenSimulator::default());
+        let from = Observation {
+            position: previous.position,
+            velocity: previous.velocity,
+        };
+        let to = Observation {
+            position: next.position,
+            velocity: next.velocity,
+        };
+        let results = inference.infer(&mut ctx, from, to, &FlatWorld);
+        assert!(results[0].error < 1.0e-4);
+        assert!(results.windows(2).all(|w| w[0].error <= w[1].error));
+        assert!(results.iter().any(|r| {
+            let c = r.input.control_states;
+            c.forward && c.sprint && !c.left && !c.right && (r.rotation.yaw - 0.7).abs() < 1.0e-3
+        }));
+        // standing still doesn't explain it.
+        assert!(results
+            .iter()
+            .all(|r| r.input.control_states != Default::default()));
+        assert_eq!(ctx.get_state().rotation, Rotation::new(0.0, 0.0));
+    }
+}
@@ -0,0 +1,159 @@
+extern crate minecraft_physics;
+
+mod common;
+
+mod tests {
+
+    use minecraft_physics::{
+        settings::physics_settings::BELOW_WORLD_DEPTH,
+        simulators::{self, prismarine_simulator::PrismarineSimulator, Simulator, World},
+        states::{physics_context::EntityPhysicsContext, player_context::ControlStateHandler},
+        world::{border::WorldBorder, caching_world::CachingWorld},
+    };
+
+    use super::common::{self, FlatWorld};
+
+    #[cfg(feature = "parallel")]
+    use minecraft_physics::simulators::batch::BatchSimulator;
+
+    /// FlatWorld with a 10 wide border around 0 0, and nothing below y = 0.
+    pub struct BorderedWorld;
+
+    impl World for BorderedWorld {
+        fn get_block(&self, pos: &glam::Vec3A) -> Option<simulators::Block> {
+            if pos.y < 0.0 {
+                return None;
+            }
+            FlatWorld.get_block(pos)
+        }
+
+        fn get_world_border(&self) -> Option<WorldBorder> {
+            Some(WorldBorder::new(0.0, 0.0, 10.0))
+        }
+
+        fn get_min_y(&self) -> Option<f32> {
+            Some(0.0)
+        }
+    }
+
+    /// nothing to stand on, with the minimum build height at y = 0.
+    pub struct VoidWorld;
+
+    impl World for VoidWorld {
+        fn get_block(&self, pos: &glam::Vec3A) -> Option<simulators::Block> {
+            Some(simulators::Block::test_new(
+                "empty".to_string(),
+                0,
+                1,
+                pos.floor(),
+                vec![],
+            ))
+        }
+
+        fn get_min_y(&self) -> Option<f32> {
+            Some(0.0)
+        }
+    }
+
+    fn walking_player(yaw: f32) -> EntityPhysicsContext {
+        common::player(
+            glam::Vec3A::new(0.5, 63.0, 0.5),
+            yaw,
+            ControlStateHandler {
+                forward: true,
+                sprint: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_caching_world_matches() {
+        let sim = PrismarineSimulator::default();
+        let mut cached = CachingWorld::new(FlatWorld);
+
+        let mut plain_ctx = walking_player(1.0);
+        let mut cached_ctx = walking_player(1.0);
+        for _ in 0..40 {
+            plain_ctx = sim.simulate(plain_ctx, &FlatWorld);
+            cached_ctx = sim.simulate(cached_ctx, &cached);
+            cached.clear();
+            assert_eq!(
+                plain_ctx.get_state().position,
+                cached_ctx.get_state().position
+            );
+            assert_eq!(
+                plain_ctx.get_state().velocity,
+                cached_ctx.get_state().velocity
+            );
+        }
+
+        let stats = cached.get_stats();
+        assert!(stats.hits > 0);
+        assert!(stats.misses > 0);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_caching_world_shared_by_batch() {
+        let cached = CachingWorld::new(FlatWorld);
+        let batch = BatchSimulator::new(PrismarineSimulator::default(), 4).unwrap();
+        let mut batched: Vec<_> = (0..16).map(|i| walking_player(i as f32 * 0.4)).collect();
+        batch.simulate_ticks_all(&mut batched, 20, &cached);
+
+        let sim = PrismarineSimulator::default();
+        for (i, ctx) in batched.iter().enumerate() {
+            let mut plain = walking_player(i as f32 * 0.4);
+            sim.simulate_ticks(&mut plain, 20, &FlatWorld);
+            assert_eq!(plain.get_state().position, ctx.get_state().position);
+        }
+
+        let stats = cached.get_stats();
+        assert!(stats.hits > 0);
+        assert!(stats.misses > 0);
+    }
+
+    #[test]
+    fn test_world_border_collision() {
+        let sim = PrismarineSimulator::default();
+
+        // walks towards -x +z, into the corner of the border.
+        let mut ctx = walking_player(std::f32::consts::PI * 0.75);
+        for _ in 0..80 {
+            ctx = sim.simulate(ctx, &BorderedWorld);
+        }
+        assert_eq!(ctx.get_state().position.x, -5.0 + 0.3);
+        assert_eq!(ctx.get_state().position.z, 5.0 - 0.3);
+        assert!(ctx.get_state().is_collided_horizontally);
+        assert!(!ctx.get_state().is_below_world);
+    }
+
+    #[test]
+    fn test_fall_below_world() {
+        let sim = PrismarineSimulator::default();
+        let threshold = VoidWorld.get_min_y().unwrap() - BELOW_WORLD_DEPTH;
+
+        // starts a few blocks above the void, already below the build height.
+        let mut ctx = common::player(
+            glam::Vec3A::new(0.5, threshold + 4.0, 0.5),
+            0.0,
+            ControlStateHandler::default(),
+        );
+        let mut ticks_above = 0;
+        while ctx.get_state().position.y >= threshold {
+            ctx = sim.simulate(ctx, &VoidWorld);
+            let state = ctx.get_state();
+            assert_eq!(
+                state.is_below_world,
+                state.position.y < threshold,
+                "y {}",
+                state.position.y
+            );
+            if !state.is_below_world {
+                ticks_above += 1;
+            }
+        }
+        assert!(ticks_above > 0);
+        assert!(ctx.get_state().is_below_world);
+    }
+}